@@ -89,12 +89,17 @@ pub fn storage(_attr: TokenStream, input: TokenStream) -> TokenStream {
                 space -= bytes;
 
                 let root = root + alloy_primitives::U256::from(slot);
-                let field = <#ty as storage::StorageType>::new(root, space as u8);
+                let offset = space as u8;
+                let new = || <#ty as storage::StorageType>::new(root, offset);
+                let name = concat!(module_path!(), "::", stringify!(#name.#ident));
                 if words > 0 {
                     slot += words;
                     space = 32;
+                    storage::claim_composite(name, root, words, new)
+                } else {
+                    storage::claim_field(name, root, offset, bytes);
+                    new()
                 }
-                field
             },
         });
 
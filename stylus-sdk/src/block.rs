@@ -6,6 +6,12 @@
 //! See also [`contract`](crate::contract), [`crypto`](crate::crypto), [`evm`](crate::evm),
 //! [`msg`](crate::msg), and [`tx`](crate::tx).
 //!
+//! Note: the Stylus VM doesn't provide a hostio for the EVM's [`BLOCKHASH`] opcode, so historical
+//! block hashes aren't available here. Programs needing them can query the [`ArbSys`] precompile.
+//!
+//! [`BLOCKHASH`]: https://www.evm.codes/#40
+//! [`ArbSys`]: https://docs.arbitrum.io/build-decentralized-apps/precompiles/reference#arbsys
+//!
 //! ```no_run
 //! use stylus_sdk::block;
 //!
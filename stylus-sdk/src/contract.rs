@@ -17,7 +17,7 @@ use crate::{
     types::AddressVM,
 };
 use alloc::vec::Vec;
-use alloy_primitives::{Address, U256};
+use alloy_primitives::{Address, B256, U256};
 
 /// Reads the invocation's calldata.
 /// The [`entrypoint`](macro@stylus_proc::entrypoint) macro uses this under the hood.
@@ -54,6 +54,19 @@ pub fn read_return_data(offset: usize, size: Option<usize>) -> Vec<u8> {
     data
 }
 
+/// Copies exactly `size` bytes of the last EVM call or deployment return result, starting at
+/// `offset`. Returns `None` if the range extends past the end of the return data, where the EVM's
+/// [`RETURNDATACOPY`] would revert.
+///
+/// [`RETURNDATACOPY`]: https://www.evm.codes/#3E
+pub fn checked_read_return_data(offset: usize, size: usize) -> Option<Vec<u8>> {
+    let end = offset.checked_add(size)?;
+    if end > return_data_len() {
+        return None;
+    }
+    Some(read_return_data(offset, Some(size)))
+}
+
 wrap_hostio!(
     /// Returns the length of the last EVM call or deployment return result, or `0` if neither have
     /// happened during the program's execution.
@@ -69,3 +82,13 @@ wrap_hostio!(
 pub fn balance() -> U256 {
     address().balance()
 }
+
+/// Gets the codehash of the account at the given address. The semantics are equivalent to that
+/// of the EVM's [`EXT_CODEHASH`] opcode, which is `0` for accounts that don't exist.
+///
+/// See [`AddressVM::has_code`] for determining whether an address is a contract.
+///
+/// [`EXT_CODEHASH`]: https://www.evm.codes/#3F
+pub fn codehash(addr: Address) -> B256 {
+    addr.code_hash()
+}
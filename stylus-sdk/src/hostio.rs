@@ -24,6 +24,7 @@ use cfg_if::cfg_if;
 
 macro_rules! vm_hooks {
     (
+        @stubs($stubs:meta)                 // when to generate stubs rather than wasm imports
         $(#[$block_meta:meta])*             // macros & docstrings to apply to all funcs
         module($link:literal, $stub:ident); // configures the wasm_import_module to link
 
@@ -31,7 +32,7 @@ macro_rules! vm_hooks {
         $($(#[$meta:meta])* $vis:vis fn $func:ident ($($arg:ident : $arg_type:ty),* ) $(-> $return_type:ty)?);*
     ) => {
         cfg_if! {
-            if #[cfg(feature = "export-abi")] {
+            if #[cfg($stubs)] {
                 // Generate a stub for each function.
                 // We use a module for the block macros & docstrings.
                 $(#[$block_meta])*
@@ -58,6 +59,9 @@ macro_rules! vm_hooks {
             }
         }
     };
+    ($($rest:tt)*) => {
+        vm_hooks! { @stubs(feature = "export-abi") $($rest)* }
+    };
 }
 
 vm_hooks! {
@@ -91,33 +95,6 @@ vm_hooks! {
     /// [`EXT_CODEHASH`]: https://www.evm.codes/#3F
     pub fn account_codehash(address: *const u8, dest: *mut u8);
 
-    /// Reads a 32-byte value from permanent storage. Stylus's storage format is identical to
-    /// that of the EVM. This means that, under the hood, this hostio is accessing the 32-byte
-    /// value stored in the EVM state trie at offset `key`, which will be `0` when not previously
-    /// set. The semantics, then, are equivalent to that of the EVM's [`SLOAD`] opcode.
-    ///
-    /// Note: the Stylus VM implements storage caching. This means that repeated calls to the same key
-    /// will cost less than in the EVM.
-    ///
-    /// [`SLOAD`]: https://www.evm.codes/#54
-    pub fn storage_load_bytes32(key: *const u8, dest: *mut u8);
-
-    /// Writes a 32-byte value to the permanent storage cache. Stylus's storage format is identical to that
-    /// of the EVM. This means that, under the hood, this hostio represents storing a 32-byte value into
-    /// the EVM state trie at offset `key`. Refunds are tabulated exactly as in the EVM. The semantics, then,
-    /// are equivalent to that of the EVM's [`SSTORE`] opcode.
-    ///
-    /// Note: because the value is cached, one must call `storage_flush_cache` to persist it.
-    ///
-    /// [`SSTORE`]: https://www.evm.codes/#55
-    pub fn storage_cache_bytes32(key: *const u8, value: *const u8);
-
-    /// Persists any dirty values in the storage cache to the EVM state trie, dropping the cache entirely if requested.
-    /// Analogous to repeated invocations of [`SSTORE`].
-    ///
-    /// [`SSTORE`]: https://www.evm.codes/#55
-    pub fn storage_flush_cache(clear: bool);
-
     /// Gets the basefee of the current block. The semantics are equivalent to that of the EVM's
     /// [`BASEFEE`] opcode.
     ///
@@ -385,6 +362,55 @@ vm_hooks! {
     pub fn tx_origin(origin: *mut u8)
 }
 
+// The storage-test backend simulates these natively, so they aren't stubbed when it's enabled.
+vm_hooks! {
+    @stubs(all(
+        feature = "export-abi",
+        not(all(any(test, feature = "storage-test"), not(target_arch = "wasm32")))
+    ))
+    module("vm_hooks", vm_storage_hooks);
+
+    /// Reads a 32-byte value from permanent storage. Stylus's storage format is identical to
+    /// that of the EVM. This means that, under the hood, this hostio is accessing the 32-byte
+    /// value stored in the EVM state trie at offset `key`, which will be `0` when not previously
+    /// set. The semantics, then, are equivalent to that of the EVM's [`SLOAD`] opcode.
+    ///
+    /// Note: the Stylus VM implements storage caching. This means that repeated calls to the same key
+    /// will cost less than in the EVM.
+    ///
+    /// [`SLOAD`]: https://www.evm.codes/#54
+    pub fn storage_load_bytes32(key: *const u8, dest: *mut u8);
+
+    /// Writes a 32-byte value to the permanent storage cache. Stylus's storage format is identical to that
+    /// of the EVM. This means that, under the hood, this hostio represents storing a 32-byte value into
+    /// the EVM state trie at offset `key`. Refunds are tabulated exactly as in the EVM. The semantics, then,
+    /// are equivalent to that of the EVM's [`SSTORE`] opcode.
+    ///
+    /// Note: because the value is cached, one must call `storage_flush_cache` to persist it.
+    ///
+    /// [`SSTORE`]: https://www.evm.codes/#55
+    pub fn storage_cache_bytes32(key: *const u8, value: *const u8);
+
+    /// Persists any dirty values in the storage cache to the EVM state trie, dropping the cache entirely if requested.
+    /// Analogous to repeated invocations of [`SSTORE`].
+    ///
+    /// [`SSTORE`]: https://www.evm.codes/#55
+    pub fn storage_flush_cache(clear: bool);
+
+    /// Reads a 32-byte value from transient storage, which will be `0` when not previously set
+    /// during the current transaction. The semantics are equivalent to that of the EVM's
+    /// [`TLOAD`] opcode.
+    ///
+    /// [`TLOAD`]: https://www.evm.codes/#5c
+    pub fn transient_load_bytes32(key: *const u8, dest: *mut u8);
+
+    /// Writes a 32-byte value to transient storage, which is discarded at the end of the
+    /// transaction. The semantics are equivalent to that of the EVM's [`TSTORE`] opcode.
+    ///
+    /// [`TSTORE`]: https://www.evm.codes/#5d
+    pub fn transient_store_bytes32(key: *const u8, value: *const u8)
+}
+
 vm_hooks! {
     #[allow(dead_code)]
     module("console", console);
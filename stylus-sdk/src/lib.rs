@@ -25,8 +25,11 @@
 #![doc(html_favicon_url = "https://arbitrum.io/assets/stylus/Arbitrum_Stylus-Logomark.png")]
 #![doc(html_logo_url = "https://arbitrum.io/assets/stylus/Arbitrum_Stylus-Logomark.png")]
 #![warn(missing_docs)]
-// Only allow the standard library in tests and for exports
-#![cfg_attr(not(any(test, feature = "export-abi")), no_std)]
+// Only allow the standard library in tests, for exports, and for the simulated storage backend
+#![cfg_attr(
+    not(any(test, feature = "export-abi", feature = "storage-test")),
+    no_std
+)]
 
 /// Use an efficient WASM allocator.
 ///
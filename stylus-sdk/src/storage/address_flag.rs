@@ -0,0 +1,113 @@
+// Copyright 2023-2024, Offchain Labs, Inc.
+// For licensing, see https://github.com/OffchainLabs/stylus-sdk-rs/blob/main/licenses/COPYRIGHT.md
+
+use super::{Erase, SimpleStorageType, StorageAddress, StorageBool, StorageGuardMut, StorageType};
+use alloy_primitives::{Address, U256};
+
+/// Accessor for an [`Address`] and a [`bool`] flag packed into a single word, such as
+/// an `(owner, initialized)` or `(token, isActive)` pair.
+///
+/// The layout matches a Solidity struct `{ address addr; bool flag; }`: the address occupies
+/// the word's lowest 20 bytes and the flag the byte just above them. Each getter and setter
+/// touches only its own bytes.
+#[derive(Debug)]
+pub struct StorageAddressFlag {
+    address: StorageAddress,
+    flag: StorageBool,
+}
+
+impl StorageAddressFlag {
+    /// Gets the underlying [`Address`] in persistent storage.
+    pub fn get_address(&self) -> Address {
+        self.address.get()
+    }
+
+    /// Sets the underlying [`Address`] in persistent storage.
+    pub fn set_address(&mut self, value: Address) {
+        self.address.set(value);
+    }
+
+    /// Gets the underlying flag in persistent storage.
+    pub fn get_flag(&self) -> bool {
+        self.flag.get()
+    }
+
+    /// Sets the underlying flag in persistent storage.
+    pub fn set_flag(&mut self, value: bool) {
+        self.flag.set(value);
+    }
+
+    /// Gets both the [`Address`] and the flag.
+    pub fn get(&self) -> (Address, bool) {
+        (self.get_address(), self.get_flag())
+    }
+
+    /// Sets both the [`Address`] and the flag.
+    pub fn set(&mut self, (address, flag): (Address, bool)) {
+        self.set_address(address);
+        self.set_flag(flag);
+    }
+}
+
+impl StorageType for StorageAddressFlag {
+    type Wraps<'a> = (Address, bool);
+    type WrapsMut<'a> = StorageGuardMut<'a, Self>;
+
+    unsafe fn new(slot: U256, offset: u8) -> Self {
+        debug_assert!(offset == 0);
+        Self {
+            address: StorageAddress::new(slot, 12),
+            flag: StorageBool::new(slot, 11),
+        }
+    }
+
+    fn load<'s>(self) -> Self::Wraps<'s> {
+        self.get()
+    }
+
+    fn load_mut<'s>(self) -> Self::WrapsMut<'s> {
+        StorageGuardMut::new(self)
+    }
+}
+
+impl<'a> SimpleStorageType<'a> for StorageAddressFlag {
+    fn set_by_wrapped(&mut self, value: Self::Wraps<'a>) {
+        self.set(value);
+    }
+}
+
+impl Erase for StorageAddressFlag {
+    fn erase(&mut self) {
+        self.address.erase();
+        self.flag.erase();
+    }
+}
+
+impl From<StorageAddressFlag> for (Address, bool) {
+    fn from(value: StorageAddressFlag) -> Self {
+        value.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{testing, GlobalStorage, StorageCache};
+    use alloy_primitives::B256;
+
+    #[test]
+    fn test_address_flag_layout() {
+        let owner = Address::repeat_byte(0xab);
+        testing::assert_round_trips::<StorageAddressFlag>([(owner, true), (Address::ZERO, false)]);
+
+        let slot = U256::from(2);
+        let mut store = unsafe { StorageAddressFlag::new(slot, 0) };
+        store.set_address(owner);
+        store.set_flag(true);
+
+        let mut expected = B256::ZERO;
+        expected[11] = 1;
+        expected[12..].copy_from_slice(owner.as_slice());
+        assert_eq!(StorageCache::get_word(slot), expected);
+    }
+}
@@ -1,11 +1,16 @@
 // Copyright 2023-2024, Offchain Labs, Inc.
 // For licensing, see https://github.com/OffchainLabs/stylus-sdk-rs/blob/main/licenses/COPYRIGHT.md
 
-use super::{Erase, StorageGuard, StorageGuardMut, StorageType};
+use super::{nest, Erase, StorageGuard, StorageGuardMut, StorageType};
 use alloy_primitives::U256;
 use core::marker::PhantomData;
 
 /// Accessor for a storage-backed array.
+///
+/// Elements are laid out as in Solidity: small primitives are packed into consecutive words,
+/// while composite elements each start on a fresh word and fill their
+/// [`REQUIRED_SLOTS`](StorageType::REQUIRED_SLOTS). Arrays may therefore nest, with
+/// `StorageArray<StorageArray<StorageU256, 3>, 3>` matching `uint256[3][3]` in row-major order.
 pub struct StorageArray<S: StorageType, const N: usize> {
     slot: U256,
     marker: PhantomData<S>,
@@ -70,7 +75,7 @@ impl<S: StorageType, const N: usize> StorageArray<S, N> {
             return None;
         }
         let (slot, offset) = self.index_slot(index);
-        Some(S::new(slot, offset))
+        Some(nest(|| S::new(slot, offset)))
     }
 
     /// Gets the underlying accessor to the element at a given index, even if out of bounds.
@@ -80,7 +85,7 @@ impl<S: StorageType, const N: usize> StorageArray<S, N> {
     /// Enables aliasing. UB if out of bounds.
     unsafe fn accessor_unchecked(&self, index: usize) -> S {
         let (slot, offset) = self.index_slot(index);
-        S::new(slot, offset)
+        nest(|| S::new(slot, offset))
     }
 
     /// Gets the element at the given index, if it exists.
@@ -131,3 +136,34 @@ impl<S: Erase, const N: usize> Erase for StorageArray<S, N> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{GlobalStorage, Storage, StorageU256, StorageU64};
+    use alloy_primitives::U64;
+
+    #[test]
+    fn test_nested_layout() {
+        type Grid = StorageArray<StorageArray<StorageU256, 3>, 3>;
+        assert_eq!(Grid::REQUIRED_SLOTS, 9);
+
+        let root = U256::from(2);
+        let mut grid = unsafe { Grid::new(root, 0) };
+        let mut row = grid.setter(1_usize).unwrap();
+        row.setter(2_usize).unwrap().set(U256::from(12));
+        let slot = root + U256::from(3 + 2);
+        assert_eq!(Storage::get_word(slot)[31], 12);
+
+        // each row of a `uint64[5][2]` spills into a second word
+        type Packed = StorageArray<StorageArray<StorageU64, 5>, 2>;
+        assert_eq!(Packed::REQUIRED_SLOTS, 4);
+        let mut packed = unsafe { Packed::new(root, 0) };
+        let mut row = packed.setter(1_usize).unwrap();
+        row.setter(4_usize).unwrap().set(U64::from(7));
+        let word = Storage::get_word(root + U256::from(2 + 1));
+        assert_eq!(word[31], 7);
+        let row = packed.get(1_usize).unwrap();
+        assert_eq!(row.get(4_usize), Some(U64::from(7)));
+    }
+}
@@ -0,0 +1,202 @@
+// Copyright 2023-2024, Offchain Labs, Inc.
+// For licensing, see https://github.com/OffchainLabs/stylus-sdk-rs/blob/main/licenses/COPYRIGHT.md
+
+use super::{Erase, GlobalStorage, Storage, StorageGuard, StorageGuardMut, StorageType};
+use crate::crypto;
+use alloy_primitives::U256;
+use core::cell::OnceCell;
+
+/// Accessor for up to 256 storage-backed boolean flags, packed into a single word.
+///
+/// Flag `i` is bit `i` of the word when read as a [`U256`], so the layout matches the common
+/// Solidity idiom `(word >> i) & 1`.
+pub struct StorageBitSet {
+    slot: U256,
+}
+
+impl StorageType for StorageBitSet {
+    type Wraps<'a> = StorageGuard<'a, StorageBitSet> where Self: 'a;
+    type WrapsMut<'a> = StorageGuardMut<'a, StorageBitSet> where Self: 'a;
+
+    unsafe fn new(slot: U256, offset: u8) -> Self {
+        debug_assert!(offset == 0);
+        Self { slot }
+    }
+
+    fn load<'s>(self) -> Self::Wraps<'s> {
+        StorageGuard::new(self)
+    }
+
+    fn load_mut<'s>(self) -> Self::WrapsMut<'s> {
+        StorageGuardMut::new(self)
+    }
+}
+
+impl StorageBitSet {
+    /// The number of flags a bitset holds.
+    pub const BITS: usize = 256;
+
+    /// Gets the flag at the given index.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not less than [`BITS`](Self::BITS).
+    pub fn get(&self, index: usize) -> bool {
+        Self::check(index);
+        self.word().bit(index)
+    }
+
+    /// Sets the flag at the given index.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not less than [`BITS`](Self::BITS).
+    pub fn set(&mut self, index: usize, value: bool) {
+        Self::check(index);
+        let mut word = self.word();
+        word.set_bit(index, value);
+        unsafe { Storage::set_word(self.slot, word.into()) };
+    }
+
+    /// Flips the flag at the given index, returning its new value.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not less than [`BITS`](Self::BITS).
+    pub fn toggle(&mut self, index: usize) -> bool {
+        let value = !self.get(index);
+        self.set(index, value);
+        value
+    }
+
+    /// Counts the flags that are set.
+    pub fn count_ones(&self) -> usize {
+        self.word().count_ones()
+    }
+
+    /// Reads the flags as a whole word.
+    fn word(&self) -> U256 {
+        Storage::get_word(self.slot).into()
+    }
+
+    /// Ensures an index is in bounds.
+    fn check(index: usize) {
+        assert!(index < Self::BITS, "StorageBitSet index out of bounds");
+    }
+}
+
+impl Erase for StorageBitSet {
+    fn erase(&mut self) {
+        unsafe { Storage::clear_word(self.slot) };
+    }
+}
+
+/// Accessor for a storage-backed bitmap over the entire [`U256`] index space,
+/// such as a record of which token ids have claimed an airdrop.
+///
+/// Flags are packed 256 to a word, starting where a [`StorageVec`](super::StorageVec) rooted at
+/// the same slot would place its elements. Flag `i` is bit `i % 256` of word `i / 256`, so each
+/// access touches only the one word holding the flag.
+pub struct StorageBitMap {
+    slot: U256,
+    base: OnceCell<U256>,
+}
+
+impl StorageType for StorageBitMap {
+    type Wraps<'a> = StorageGuard<'a, StorageBitMap> where Self: 'a;
+    type WrapsMut<'a> = StorageGuardMut<'a, StorageBitMap> where Self: 'a;
+
+    unsafe fn new(slot: U256, offset: u8) -> Self {
+        debug_assert!(offset == 0);
+        Self {
+            slot,
+            base: OnceCell::new(),
+        }
+    }
+
+    fn load<'s>(self) -> Self::Wraps<'s> {
+        StorageGuard::new(self)
+    }
+
+    fn load_mut<'s>(self) -> Self::WrapsMut<'s> {
+        StorageGuardMut::new(self)
+    }
+}
+
+impl StorageBitMap {
+    /// Gets the flag at the given index.
+    pub fn get(&self, index: U256) -> bool {
+        let (word, bit) = self.index_word(index);
+        word.get(bit)
+    }
+
+    /// Sets the flag at the given index.
+    pub fn set(&mut self, index: U256, value: bool) {
+        let (mut word, bit) = self.index_word(index);
+        word.set(bit, value);
+    }
+
+    /// Flips the flag at the given index, returning its new value.
+    pub fn toggle(&mut self, index: U256) -> bool {
+        let (mut word, bit) = self.index_word(index);
+        word.toggle(bit)
+    }
+
+    /// Determines the word holding the flag at an index, and the flag's position within it.
+    fn index_word(&self, index: U256) -> (StorageBitSet, usize) {
+        let bits = U256::from(StorageBitSet::BITS);
+        let slot = self.base().wrapping_add(index / bits);
+        let bit = (index % bits).to();
+        (unsafe { StorageBitSet::new(slot, 0) }, bit)
+    }
+
+    /// Determines where in storage the words start. Could be made `const` in the future.
+    fn base(&self) -> &U256 {
+        self.base
+            .get_or_init(|| crypto::keccak(self.slot.to_be_bytes::<32>()).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B256;
+
+    #[test]
+    fn test_bitset() {
+        let mut set = unsafe { StorageBitSet::new(U256::ZERO, 0) };
+        set.set(0, true);
+        set.set(255, true);
+        assert!(set.toggle(7));
+        assert!(!set.toggle(0));
+        assert!(set.get(7) && set.get(255) && !set.get(0));
+        assert_eq!(set.count_ones(), 2);
+        let word = U256::from(1) << 255 | U256::from(1 << 7);
+        assert_eq!(Storage::get_word(U256::ZERO), B256::from(word));
+
+        set.erase();
+        assert_eq!(set.count_ones(), 0);
+    }
+
+    #[test]
+    fn test_bitmap() {
+        let mut map = unsafe { StorageBitMap::new(U256::ZERO, 0) };
+        let id = U256::from(256 * 3 + 5);
+        assert!(!map.get(id));
+        map.set(id, true);
+        assert!(map.get(id) && !map.get(id - U256::from(256)));
+        assert!(map.toggle(U256::MAX));
+        assert!(map.get(U256::MAX));
+
+        let base: U256 = crypto::keccak(B256::ZERO).into();
+        let word: U256 = Storage::get_word(base + U256::from(3)).into();
+        assert_eq!(word, U256::from(1 << 5));
+    }
+
+    #[test]
+    #[should_panic(expected = "StorageBitSet index out of bounds")]
+    fn test_bitset_bounds() {
+        let set = unsafe { StorageBitSet::new(U256::ZERO, 0) };
+        set.get(256);
+    }
+}
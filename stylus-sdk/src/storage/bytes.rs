@@ -1,13 +1,16 @@
 // Copyright 2022-2024, Offchain Labs, Inc.
 // For licensing, see https://github.com/OffchainLabs/stylus-sdk-rs/blob/main/licenses/COPYRIGHT.md
 
-use super::{Erase, GlobalStorage, Storage, StorageB8, StorageGuard, StorageGuardMut, StorageType};
+use super::{
+    Erase, GlobalStorage, Storage, StorageB8, StorageError, StorageGuard, StorageGuardMut,
+    StorageType,
+};
 use crate::crypto;
 use alloc::{
     string::{String, ToString},
     vec::Vec,
 };
-use alloy_primitives::{U256, U8};
+use alloy_primitives::{Bytes, B256, U256, U8};
 use core::cell::OnceCell;
 
 /// Accessor for storage-backed bytes.
@@ -35,6 +38,13 @@ impl StorageType for StorageBytes {
     fn load_mut<'s>(self) -> Self::WrapsMut<'s> {
         StorageGuardMut::new(self)
     }
+
+    fn slot_count(&self) -> usize {
+        match self.len() {
+            len if len < 32 => 1,
+            len => ((len + 31) / 32).saturating_add(1),
+        }
+    }
 }
 
 impl StorageBytes {
@@ -44,18 +54,28 @@ impl StorageBytes {
     }
 
     /// Gets the number of bytes stored.
+    ///
+    /// # Panics
+    ///
+    /// If the stored length doesn't fit in a [`usize`], which is only possible for corrupt storage.
     pub fn len(&self) -> usize {
+        self.stored_len()
+            .try_into()
+            .expect("StorageBytes length exceeds usize")
+    }
+
+    /// Decodes the length from the root slot, without bounding it.
+    fn stored_len(&self) -> U256 {
         let word = Storage::get_word(self.root);
 
         // check if the data is short
         let slot: &[u8] = word.as_ref();
         if slot[31] & 1 == 0 {
-            return (slot[31] / 2) as usize;
+            return U256::from(slot[31] / 2);
         }
 
         let word: U256 = word.into();
-        let len = word / U256::from(2);
-        len.try_into().unwrap()
+        word / U256::from(2)
     }
 
     /// Overwrites the collection's length, moving bytes as needed.
@@ -126,6 +146,54 @@ impl StorageBytes {
         assign!(slot);
     }
 
+    /// Appends bytes to the end, writing each affected word once rather than byte by byte.
+    /// Since only the tail words are touched, building a large value across several calls
+    /// costs time proportional to the bytes appended rather than to the whole value.
+    ///
+    /// # Panics
+    ///
+    /// If the length would overflow.
+    pub fn append_bytes(&mut self, bytes: impl AsRef<[u8]>) {
+        let bytes = bytes.as_ref();
+        let old = self.len();
+        let len = old
+            .checked_add(bytes.len())
+            .expect("StorageBytes length overflow");
+
+        if len < 32 {
+            let mut word = Storage::get_word(self.root);
+            word[old..len].copy_from_slice(bytes);
+            word[31] = (len * 2) as u8;
+            return unsafe { Storage::set_word(self.root, word) };
+        }
+
+        // convert to multi-word representation by writing the short contents out too
+        let mut index = old;
+        let mut data = Vec::new();
+        if old < 32 {
+            data.extend_from_slice(&Storage::get_word(self.root)[..old]);
+            index = 0;
+        }
+        data.extend_from_slice(bytes);
+
+        let mut rest = &data[..];
+        while !rest.is_empty() {
+            let slot = self.base() + U256::from(index / 32);
+            let offset = index % 32;
+            let count = rest.len().min(32 - offset);
+
+            let mut word = match offset {
+                0 => B256::ZERO,
+                _ => Storage::get_word(slot),
+            };
+            word[offset..][..count].copy_from_slice(&rest[..count]);
+            unsafe { Storage::set_word(slot, word) };
+            index += count;
+            rest = &rest[count..];
+        }
+        unsafe { self.write_len(len) };
+    }
+
     /// Removes and returns the last byte, if it exists.
     /// As an optimization, underlying storage slots are only erased when all bytes in
     /// a given word are freed when in the multi-word representation.
@@ -206,12 +274,34 @@ impl StorageBytes {
         bytes
     }
 
+    /// Gets the full contents of the collection, provided there are at most `max_len` bytes.
+    ///
+    /// Unlike [`get_bytes`](Self::get_bytes), this won't attempt to read an absurd number
+    /// of slots when the stored length is corrupt.
+    pub fn try_get_bytes(&self, max_len: usize) -> Result<Vec<u8>, StorageError> {
+        if self.stored_len() > U256::from(max_len) {
+            return Err(StorageError::LengthExceedsMaximum);
+        }
+        Ok(self.get_bytes())
+    }
+
     /// Overwrites the contents of the collection, erasing what was previously stored.
     pub fn set_bytes(&mut self, bytes: impl AsRef<[u8]>) {
         self.erase();
         self.extend(bytes.as_ref());
     }
 
+    /// Gets the full contents of the collection as [`Bytes`], the type used when ABI-decoding.
+    pub fn get_alloy_bytes(&self) -> Bytes {
+        self.get_bytes().into()
+    }
+
+    /// Overwrites the contents of the collection with [`Bytes`], such as those decoded
+    /// from calldata, erasing what was previously stored.
+    pub fn set_alloy_bytes(&mut self, bytes: &Bytes) {
+        self.set_bytes(bytes);
+    }
+
     /// Determines the slot and offset for the element at an index.
     fn index_slot(&self, index: usize) -> (U256, u8) {
         let slot = match self.len() {
@@ -259,6 +349,12 @@ impl<'a> Extend<&'a u8> for StorageBytes {
 }
 
 /// Accessor for storage-backed bytes
+///
+/// As in Solidity, a string's header fills an entire slot and longer contents spill into the
+/// slots starting at the keccak of that header slot. A [`StorageVec`](super::StorageVec) of
+/// strings is therefore laid out like a `string[]`, with element `i`'s header at
+/// [`data_base`](super::StorageVec::data_base) `+ i`. Use [`grow`](super::StorageVec::grow)
+/// to append an element and [`setter`](super::StorageVec::setter) to modify one.
 pub struct StorageString(pub StorageBytes);
 
 impl StorageType for StorageString {
@@ -276,6 +372,10 @@ impl StorageType for StorageString {
     fn load_mut<'s>(self) -> Self::WrapsMut<'s> {
         StorageGuardMut::new(self)
     }
+
+    fn slot_count(&self) -> usize {
+        self.0.slot_count()
+    }
 }
 
 impl StorageString {
@@ -297,11 +397,20 @@ impl StorageString {
     }
 
     /// Gets the underlying [`String`], ignoring any invalid data.
+    /// Invalid UTF-8 sequences, which can only be written through raw storage access,
+    /// are replaced with [`char::REPLACEMENT_CHARACTER`].
     pub fn get_string(&self) -> String {
         let bytes = self.0.get_bytes();
         String::from_utf8_lossy(&bytes).into()
     }
 
+    /// Gets the underlying [`String`], ignoring any invalid data, provided there are at most
+    /// `max_len` bytes. See [`StorageBytes::try_get_bytes`].
+    pub fn try_get_string(&self, max_len: usize) -> Result<String, StorageError> {
+        let bytes = self.0.try_get_bytes(max_len)?;
+        Ok(String::from_utf8_lossy(&bytes).into())
+    }
+
     /// Overwrites the underlying [`String`], erasing what was previously stored.
     pub fn set_str(&mut self, text: impl AsRef<str>) {
         self.erase();
@@ -324,3 +433,137 @@ impl Extend<char> for StorageString {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::StorageVec;
+
+    #[test]
+    fn test_corrupt_len() {
+        let mut bytes = unsafe { StorageBytes::new(U256::ZERO, 0) };
+        bytes.set_bytes([7; 40]);
+        assert_eq!(bytes.try_get_bytes(40), Ok([7; 40].to_vec()));
+        assert_eq!(
+            bytes.try_get_bytes(39),
+            Err(StorageError::LengthExceedsMaximum)
+        );
+
+        unsafe { Storage::set_word(U256::ZERO, U256::MAX.into()) };
+        assert_eq!(
+            bytes.try_get_bytes(1 << 20),
+            Err(StorageError::LengthExceedsMaximum)
+        );
+
+        let string = unsafe { StorageString::new(U256::ZERO, 0) };
+        assert_eq!(
+            string.try_get_string(1 << 20),
+            Err(StorageError::LengthExceedsMaximum)
+        );
+    }
+
+    #[test]
+    fn test_push_across_boundary() {
+        let mut blob = unsafe { StorageBytes::new(U256::ZERO, 0) };
+        let data: Vec<u8> = (1..=100).collect();
+        for (i, byte) in data.iter().enumerate() {
+            blob.push(*byte);
+            let root = Storage::get_word(U256::ZERO);
+            match i + 1 {
+                31 => assert_eq!((root[31], root[30]), (62, 31)),
+                32 => assert_eq!(U256::from_be_bytes(root.0), U256::from(65)),
+                _ => {}
+            }
+        }
+        assert_eq!((blob.len(), blob.get_bytes()), (100, data.clone()));
+
+        let tail = Storage::get_word(*blob.base() + U256::from(3));
+        assert_eq!((tail[3], tail[4]), (100, 0));
+        blob.set_bytes(&data[..5]);
+        assert_eq!(blob.get_bytes(), &data[..5]);
+    }
+
+    #[test]
+    fn test_append_bytes() {
+        let mut blob = unsafe { StorageBytes::new(U256::ZERO, 0) };
+        let data: Vec<u8> = (0..100).collect();
+        blob.append_bytes(&data[..10]);
+        blob.append_bytes(&data[10..20]);
+        assert_eq!(blob.get_bytes(), &data[..20]);
+
+        blob.append_bytes(&data[20..40]);
+        assert_eq!(blob.get_bytes(), &data[..40]);
+        blob.append_bytes(&data[40..]);
+        blob.append_bytes([]);
+        assert_eq!(blob.get_bytes(), data);
+
+        let mut pushed = unsafe { StorageBytes::new(U256::from(1), 0) };
+        pushed.set_bytes(&data);
+        for i in 0..4 {
+            let offset = U256::from(i);
+            let expected = Storage::get_word(*pushed.base() + offset);
+            assert_eq!(Storage::get_word(*blob.base() + offset), expected);
+        }
+    }
+
+    #[test]
+    fn test_slot_count() {
+        let mut bytes = unsafe { StorageBytes::new(U256::ZERO, 0) };
+        bytes.set_bytes([1; 31]);
+        assert_eq!(bytes.slot_count(), 1);
+        bytes.set_bytes([1; 33]);
+        assert_eq!(bytes.slot_count(), 3);
+    }
+
+    #[test]
+    fn test_alloy_bytes() {
+        let mut data = unsafe { StorageBytes::new(U256::ZERO, 0) };
+        let payload = Bytes::from_static(&[0xab; 33]);
+        data.set_alloy_bytes(&payload);
+        assert_eq!(data.get_alloy_bytes(), payload);
+        assert_eq!(data.get_bytes(), payload.to_vec());
+    }
+
+    #[test]
+    fn test_string_vec() {
+        let mut names: StorageVec<StorageString> = unsafe { StorageVec::new(U256::ZERO, 0) };
+        let long = "a string long enough to spill past its header slot";
+        names.grow().set_str("short");
+        names.grow().set_str(long);
+        assert_eq!(names.len(), 2);
+        assert_eq!(names.getter(0).unwrap().get_string(), "short");
+        assert_eq!(names.getter(1).unwrap().get_string(), long);
+
+        let header = names.data_base() + U256::from(1);
+        let spill: U256 = crypto::keccak(header.to_be_bytes::<32>()).into();
+        let word = Storage::get_word(spill);
+        assert_eq!(&word[..], &long.as_bytes()[..32]);
+
+        names.setter(0).unwrap().set_str("renamed");
+        assert_eq!(names.getter(0).unwrap().get_string(), "renamed");
+        assert_eq!(names.getter(1).unwrap().get_string(), long);
+    }
+
+    #[test]
+    fn test_string_encodings() {
+        let mut text = unsafe { StorageString::new(U256::ZERO, 0) };
+        let cases = ["", &"a".repeat(31), &"b".repeat(32), &"c".repeat(100)];
+        for case in cases {
+            text.set_str(case);
+            assert_eq!((text.get_string().as_str(), text.len()), (case, case.len()));
+
+            let root = Storage::get_word(U256::ZERO);
+            match case.len() {
+                len @ 0..=31 => {
+                    assert_eq!(root[31] as usize, len * 2);
+                    assert_eq!(&root[..len], case.as_bytes());
+                }
+                len => assert_eq!(U256::from_be_bytes(root.0), U256::from(len * 2 + 1)),
+            }
+        }
+
+        let mut bytes = unsafe { StorageBytes::new(U256::ZERO, 0) };
+        bytes.set_bytes(b"ok\xff");
+        assert_eq!(text.get_string(), "ok\u{fffd}");
+    }
+}
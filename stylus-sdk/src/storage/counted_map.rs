@@ -0,0 +1,185 @@
+// Copyright 2023-2024, Offchain Labs, Inc.
+// For licensing, see https://github.com/OffchainLabs/stylus-sdk-rs/blob/main/licenses/COPYRIGHT.md
+
+use super::{
+    Erase, GlobalStorage, SimpleStorageType, Storage, StorageGuard, StorageGuardMut, StorageKey,
+    StorageMap, StorageType, StorageU256,
+};
+use alloy_primitives::{B256, U256};
+
+/// Accessor for a storage-backed map that keeps track of how many keys it holds.
+///
+/// The map's entries are laid out exactly like a [`StorageMap`] rooted at the first slot,
+/// while the count lives in the slot immediately after it.
+///
+/// Note: persistent storage can't distinguish an absent key from one holding the zero-value,
+/// so a key counts toward [`len`](Self::len) only while the first word of its value is nonzero.
+/// Inserting the zero-value is equivalent to deleting the key.
+pub struct CountedStorageMap<K: StorageKey, V: StorageType> {
+    slot: U256,
+    map: StorageMap<K, V>,
+    count: StorageU256,
+}
+
+impl<K, V> StorageType for CountedStorageMap<K, V>
+where
+    K: StorageKey,
+    V: StorageType,
+{
+    type Wraps<'a> = StorageGuard<'a, CountedStorageMap<K, V>> where Self: 'a;
+    type WrapsMut<'a> = StorageGuardMut<'a, CountedStorageMap<K, V>> where Self: 'a;
+
+    const REQUIRED_SLOTS: usize = 2;
+
+    unsafe fn new(slot: U256, offset: u8) -> Self {
+        debug_assert!(offset == 0);
+        Self {
+            slot,
+            map: StorageMap::new(slot, 0),
+            count: StorageU256::new(slot + U256::from(1), 0),
+        }
+    }
+
+    fn load<'s>(self) -> Self::Wraps<'s> {
+        StorageGuard::new(self)
+    }
+
+    fn load_mut<'s>(self) -> Self::WrapsMut<'s> {
+        StorageGuardMut::new(self)
+    }
+}
+
+impl<K, V> CountedStorageMap<K, V>
+where
+    K: StorageKey,
+    V: StorageType,
+{
+    /// Returns the number of keys holding a nonzero value.
+    pub fn len(&self) -> usize {
+        self.count.get().to()
+    }
+
+    /// Returns `true` if no key holds a nonzero value.
+    pub fn is_empty(&self) -> bool {
+        self.count.get().is_zero()
+    }
+
+    /// Returns `true` if the given key holds a nonzero value.
+    pub fn contains_key(&self, key: &K) -> bool {
+        Self::occupied(self.key_slot(key))
+    }
+
+    /// Gets an accessor to the element at the given key, or the zero-value if none is there.
+    /// Note: the accessor is protected by a [`StorageGuard`], which restricts its lifetime
+    /// to that of `&self`.
+    pub fn getter(&self, key: K) -> StorageGuard<'_, V> {
+        self.map.getter(key)
+    }
+
+    /// Gets the element at the given key, or the zero value if none is there.
+    pub fn get(&self, key: K) -> V::Wraps<'_> {
+        self.map.get(key)
+    }
+
+    /// Determines the slot of the value at the given key.
+    fn key_slot(&self, key: &K) -> U256 {
+        key.to_slot(self.slot.into())
+    }
+
+    /// Whether a value's first word marks its key as present.
+    fn occupied(slot: U256) -> bool {
+        Storage::get_word(slot) != B256::ZERO
+    }
+
+    /// Updates the count after a key goes from being present (or not) to being present (or not).
+    fn recount(&mut self, before: bool, after: bool) {
+        let count = self.count.get();
+        match (before, after) {
+            (false, true) => self.count.set(count + U256::from(1)),
+            (true, false) => self.count.set(count - U256::from(1)),
+            _ => {}
+        }
+    }
+}
+
+impl<'a, K, V> CountedStorageMap<K, V>
+where
+    K: StorageKey,
+    V: SimpleStorageType<'a>,
+{
+    /// Sets the element at a given key, overwriting what may have been there.
+    pub fn insert(&mut self, key: K, value: V::Wraps<'a>) {
+        let slot = self.key_slot(&key);
+        let before = Self::occupied(slot);
+        self.map.insert(key, value);
+        self.recount(before, Self::occupied(slot));
+    }
+
+    /// Replace the element at the given key.
+    /// Returns the old element, or the zero-value if none was there.
+    pub fn replace(&mut self, key: K, value: V::Wraps<'a>) -> V::Wraps<'a> {
+        let slot = self.key_slot(&key);
+        let before = Self::occupied(slot);
+        let prior = self.map.replace(key, value);
+        self.recount(before, Self::occupied(slot));
+        prior
+    }
+
+    /// Remove the element at the given key.
+    /// Returns the element, or the zero-value if none was there.
+    pub fn take(&mut self, key: K) -> V::Wraps<'a> {
+        let before = self.contains_key(&key);
+        let value = self.map.take(key);
+        self.recount(before, false);
+        value
+    }
+}
+
+impl<K, V> CountedStorageMap<K, V>
+where
+    K: StorageKey,
+    V: Erase,
+{
+    /// Delete the element at the given key, if it exists.
+    pub fn delete(&mut self, key: K) {
+        let before = self.contains_key(&key);
+        self.map.delete(key);
+        self.recount(before, false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{testing, StorageU64};
+    use alloy_primitives::{Address, U64};
+
+    #[test]
+    fn test_counted_map() {
+        testing::reset();
+        let mut map: CountedStorageMap<Address, StorageU64> =
+            unsafe { CountedStorageMap::new(U256::ZERO, 0) };
+        let alice = Address::repeat_byte(0xa1);
+        let bob = Address::repeat_byte(0xb0);
+        assert!(map.is_empty());
+
+        map.insert(alice, U64::from(1));
+        map.insert(alice, U64::from(2));
+        assert_eq!(map.len(), 1);
+        assert!(map.contains_key(&alice));
+
+        map.insert(bob, U64::ZERO);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.replace(bob, U64::from(3)), U64::ZERO);
+        assert_eq!(map.len(), 2);
+
+        map.delete(alice);
+        map.delete(alice);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.get(alice), U64::ZERO);
+
+        assert_eq!(map.take(bob), U64::from(3));
+        assert_eq!(map.take(bob), U64::ZERO);
+        assert!(map.is_empty());
+    }
+}
@@ -0,0 +1,198 @@
+// Copyright 2023-2024, Offchain Labs, Inc.
+// For licensing, see https://github.com/OffchainLabs/stylus-sdk-rs/blob/main/licenses/COPYRIGHT.md
+
+use super::{Erase, GlobalStorage, Storage, StorageGuardMut, StorageType};
+use alloc::vec;
+use alloy_primitives::{B256, U256};
+use core::marker::PhantomData;
+
+/// Trait for enums with data that can be stored as a tagged union in a [`StorageEnum`].
+///
+/// Each variant is identified by a one-byte tag and serializes its data into a payload
+/// of exactly [`PAYLOAD_BYTES`](Self::PAYLOAD_BYTES) bytes. Variants with less data leave
+/// the rest of the payload zeroed.
+///
+/// Note: storage that has never been written decodes as tag `0` with an all-zero payload,
+/// so implementations should give that encoding to the enum's default variant.
+pub trait StorageEnumValue: Sized {
+    /// The size in bytes of the largest variant's payload.
+    const PAYLOAD_BYTES: usize;
+
+    /// Returns the tag identifying the variant.
+    fn tag(&self) -> u8;
+
+    /// Serializes the variant's data into `payload`, which is zeroed and exactly
+    /// [`PAYLOAD_BYTES`](Self::PAYLOAD_BYTES) long.
+    fn encode_payload(&self, payload: &mut [u8]);
+
+    /// Reconstructs a variant from its tag and payload, or `None` if the tag is unknown.
+    fn decode(tag: u8, payload: &[u8]) -> Option<Self>;
+}
+
+/// Accessor for a storage-backed enum with data, laid out as a tagged union.
+///
+/// The tag and payload are written as one contiguous run of bytes, starting from the left
+/// of the first word and spilling into the words after it. The tag occupies the first byte,
+/// so the accessor fills `(1 + T::PAYLOAD_BYTES + 31) / 32` words regardless of which
+/// variant is stored. Writing a variant overwrites every word, so no stale bytes from a
+/// larger variant remain.
+///
+/// The payload format is entirely up to the [`StorageEnumValue`] implementation, and must
+/// remain stable for as long as the data lives in storage.
+///
+/// Note: since `T` is foreign to this crate, coherence rules prevent implementing
+/// `From<StorageEnum<T>> for T`, so this accessor isn't a
+/// [`SimpleStorageType`](super::SimpleStorageType). Use [`get`](Self::get) and
+/// [`set`](Self::set) instead.
+pub struct StorageEnum<T: StorageEnumValue> {
+    slot: U256,
+    marker: PhantomData<T>,
+}
+
+impl<T: StorageEnumValue> StorageEnum<T> {
+    /// The number of bytes spanned by the tag and payload.
+    const BYTES: usize = 1 + T::PAYLOAD_BYTES;
+
+    /// Gets the underlying value in persistent storage.
+    ///
+    /// # Panics
+    ///
+    /// If the stored tag doesn't correspond to any variant.
+    pub fn get(&self) -> T {
+        let mut data = vec![0; Self::BYTES];
+        for (i, chunk) in data.chunks_mut(32).enumerate() {
+            let word = Storage::get_word(self.slot + U256::from(i));
+            chunk.copy_from_slice(&word[..chunk.len()]);
+        }
+        T::decode(data[0], &data[1..]).expect("invalid StorageEnum tag")
+    }
+
+    /// Sets the underlying value in persistent storage.
+    pub fn set(&mut self, value: T) {
+        let mut data = vec![0; Self::BYTES];
+        data[0] = value.tag();
+        value.encode_payload(&mut data[1..]);
+        for (i, chunk) in data.chunks(32).enumerate() {
+            let mut word = B256::ZERO;
+            word[..chunk.len()].copy_from_slice(chunk);
+            unsafe { Storage::set_word(self.slot + U256::from(i), word) };
+        }
+    }
+}
+
+impl<T: StorageEnumValue> StorageType for StorageEnum<T> {
+    type Wraps<'a> = T where Self: 'a;
+    type WrapsMut<'a> = StorageGuardMut<'a, Self> where Self: 'a;
+
+    const REQUIRED_SLOTS: usize = (Self::BYTES + 31) / 32;
+
+    unsafe fn new(slot: U256, offset: u8) -> Self {
+        debug_assert!(offset == 0);
+        Self {
+            slot,
+            marker: PhantomData,
+        }
+    }
+
+    fn load<'s>(self) -> Self::Wraps<'s>
+    where
+        Self: 's,
+    {
+        self.get()
+    }
+
+    fn load_mut<'s>(self) -> Self::WrapsMut<'s>
+    where
+        Self: 's,
+    {
+        StorageGuardMut::new(self)
+    }
+}
+
+impl<T: StorageEnumValue> Erase for StorageEnum<T> {
+    fn erase(&mut self) {
+        for i in 0..Self::REQUIRED_SLOTS {
+            unsafe { Storage::clear_word(self.slot + U256::from(i)) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Address, FixedBytes};
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Order {
+        Open,
+        Filled { amount: u64, buyer: Address },
+        Cancelled(FixedBytes<32>),
+    }
+
+    impl StorageEnumValue for Order {
+        const PAYLOAD_BYTES: usize = 32;
+
+        fn tag(&self) -> u8 {
+            match self {
+                Self::Open => 0,
+                Self::Filled { .. } => 1,
+                Self::Cancelled(_) => 2,
+            }
+        }
+
+        fn encode_payload(&self, payload: &mut [u8]) {
+            match self {
+                Self::Open => {}
+                Self::Filled { amount, buyer } => {
+                    payload[..8].copy_from_slice(&amount.to_be_bytes());
+                    payload[8..28].copy_from_slice(buyer.as_slice());
+                }
+                Self::Cancelled(reason) => payload.copy_from_slice(reason.as_slice()),
+            }
+        }
+
+        fn decode(tag: u8, payload: &[u8]) -> Option<Self> {
+            match tag {
+                0 => Some(Self::Open),
+                1 => Some(Self::Filled {
+                    amount: u64::from_be_bytes(payload[..8].try_into().unwrap()),
+                    buyer: Address::from_slice(&payload[8..28]),
+                }),
+                2 => Some(Self::Cancelled(FixedBytes::from_slice(payload))),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_storage_enum() {
+        assert_eq!(StorageEnum::<Order>::REQUIRED_SLOTS, 2);
+        let filled = Order::Filled {
+            amount: 7,
+            buyer: Address::repeat_byte(0xb0),
+        };
+        let cancelled = Order::Cancelled(FixedBytes::repeat_byte(0xcc));
+
+        let slot = U256::from(4);
+        let mut store = unsafe { StorageEnum::<Order>::new(slot, 0) };
+        assert_eq!(store.get(), Order::Open);
+        for value in [filled, cancelled.clone(), Order::Open] {
+            store.set(value.clone());
+            assert_eq!(unsafe { StorageEnum::<Order>::new(slot, 0) }.load(), value);
+        }
+
+        store.set(cancelled);
+        store.set(Order::Filled {
+            amount: 1,
+            buyer: Address::ZERO,
+        });
+        let mut expected = B256::ZERO;
+        expected[0] = 1;
+        expected[8] = 1;
+        assert_eq!(Storage::get_word(slot), expected);
+        assert_eq!(Storage::get_word(slot + U256::from(1)), B256::ZERO);
+
+        store.erase();
+        assert_eq!(store.get(), Order::Open);
+    }
+}
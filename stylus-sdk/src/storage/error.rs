@@ -0,0 +1,17 @@
+// Copyright 2023-2024, Offchain Labs, Inc.
+// For licensing, see https://github.com/OffchainLabs/stylus-sdk-rs/blob/main/licenses/COPYRIGHT.md
+
+/// Represents a failure to access a storage-backed collection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageError {
+    /// The collection's stored length exceeds the maximum the caller is willing to read,
+    /// which for well-formed storage indicates corruption or adversarial state.
+    LengthExceedsMaximum,
+    /// The collection can't grow any further, either because its length would overflow
+    /// or because the slots of its elements couldn't be addressed.
+    LengthOverflow,
+    /// An index doesn't fit in a [`usize`], so it can't refer to any element.
+    IndexOverflow,
+    /// A stored value is smaller than the amount being subtracted from it.
+    InsufficientBalance,
+}
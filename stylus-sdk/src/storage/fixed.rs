@@ -0,0 +1,396 @@
+// Copyright 2023-2024, Offchain Labs, Inc.
+// For licensing, see https://github.com/OffchainLabs/stylus-sdk-rs/blob/main/licenses/COPYRIGHT.md
+
+use super::{Erase, SimpleStorageType, StorageGuardMut, StorageI256, StorageType, StorageU256};
+use alloy_primitives::{Sign, I256, U256, U512};
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Computes `a * b / den` with a 512-bit intermediate, returning `None` if `den` is zero
+/// or the result doesn't fit in a [`U256`].
+pub(crate) fn mul_div(a: U256, b: U256, den: U256) -> Option<U256> {
+    if den.is_zero() {
+        return None;
+    }
+    let wide: U512 = a.widening_mul(b);
+    let quotient = wide / U512::from(den);
+    let (low, high) = quotient.as_limbs().split_at(U256::LIMBS);
+    high.iter()
+        .all(|&limb| limb == 0)
+        .then(|| U256::from_limbs_slice(low))
+}
+
+/// An unsigned fixed-point decimal with `D` decimals, represented as a [`U256`] scaled by `10^D`.
+///
+/// Note: `D` must not exceed 77, the largest power of ten representable in a [`U256`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UFixed<const D: u8>(U256);
+
+impl<const D: u8> UFixed<D> {
+    /// The fixed-point representation of zero.
+    pub const ZERO: Self = Self(U256::ZERO);
+
+    /// Creates a fixed-point value from its scaled integer representation.
+    pub const fn from_raw(raw: U256) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the scaled integer representation.
+    pub const fn into_raw(self) -> U256 {
+        self.0
+    }
+
+    /// The factor by which values are scaled, `10^D`.
+    pub fn scale() -> U256 {
+        U256::from(10).pow(U256::from(D))
+    }
+
+    /// Creates a fixed-point value from a whole number, if representable.
+    pub fn from_integer(value: U256) -> Option<Self> {
+        value.checked_mul(Self::scale()).map(Self)
+    }
+
+    /// Returns the whole-number part, rounding down.
+    pub fn to_integer(self) -> U256 {
+        self.0 / Self::scale()
+    }
+
+    /// Checked addition. Returns `None` on overflow.
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.0.checked_add(rhs.0).map(Self)
+    }
+
+    /// Checked subtraction. Returns `None` on underflow.
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).map(Self)
+    }
+
+    /// Scale-preserving multiplication, rounding down. Returns `None` on overflow.
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        mul_div(self.0, rhs.0, Self::scale()).map(Self)
+    }
+
+    /// Scale-preserving division, rounding down. Returns `None` on overflow or division by zero.
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        mul_div(self.0, Self::scale(), rhs.0).map(Self)
+    }
+}
+
+impl<const D: u8> Add for UFixed<D> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs)
+            .expect("fixed-point addition overflow")
+    }
+}
+
+impl<const D: u8> Sub for UFixed<D> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs)
+            .expect("fixed-point subtraction underflow")
+    }
+}
+
+impl<const D: u8> Mul for UFixed<D> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.checked_mul(rhs)
+            .expect("fixed-point multiplication overflow")
+    }
+}
+
+impl<const D: u8> Div for UFixed<D> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.checked_div(rhs).expect("fixed-point division failed")
+    }
+}
+
+/// Accessor for a storage-backed [`UFixed`].
+///
+/// The value is stored as its scaled integer representation in a full word,
+/// exactly as a [`StorageU256`] would store it.
+#[derive(Debug)]
+pub struct StorageUFixed<const D: u8> {
+    inner: StorageU256,
+}
+
+impl<const D: u8> StorageUFixed<D> {
+    /// Gets the underlying [`UFixed`] in persistent storage.
+    pub fn get(&self) -> UFixed<D> {
+        UFixed::from_raw(self.inner.get())
+    }
+
+    /// Sets the underlying [`UFixed`] in persistent storage.
+    pub fn set(&mut self, value: UFixed<D>) {
+        self.inner.set(value.into_raw());
+    }
+}
+
+impl<const D: u8> StorageType for StorageUFixed<D> {
+    type Wraps<'a> = UFixed<D>;
+    type WrapsMut<'a> = StorageGuardMut<'a, Self>;
+
+    unsafe fn new(slot: U256, offset: u8) -> Self {
+        Self {
+            inner: StorageU256::new(slot, offset),
+        }
+    }
+
+    fn load<'s>(self) -> Self::Wraps<'s> {
+        self.get()
+    }
+
+    fn load_mut<'s>(self) -> Self::WrapsMut<'s> {
+        StorageGuardMut::new(self)
+    }
+}
+
+impl<'a, const D: u8> SimpleStorageType<'a> for StorageUFixed<D> {
+    fn set_by_wrapped(&mut self, value: Self::Wraps<'a>) {
+        self.set(value);
+    }
+}
+
+impl<const D: u8> Erase for StorageUFixed<D> {
+    fn erase(&mut self) {
+        self.inner.erase();
+    }
+}
+
+impl<const D: u8> From<StorageUFixed<D>> for UFixed<D> {
+    fn from(value: StorageUFixed<D>) -> Self {
+        value.get()
+    }
+}
+
+/// Computes `a * b / den` for signed values, rounding toward zero. Returns `None` if `den` is
+/// zero or the result doesn't fit in an [`I256`].
+fn signed_mul_div(a: I256, b: I256, den: I256) -> Option<I256> {
+    let abs = mul_div(a.unsigned_abs(), b.unsigned_abs(), den.unsigned_abs())?;
+    let negative = a.is_negative() ^ b.is_negative() ^ den.is_negative();
+    let sign = if negative {
+        Sign::Negative
+    } else {
+        Sign::Positive
+    };
+    I256::checked_from_sign_and_abs(sign, abs)
+}
+
+/// A signed fixed-point decimal with `D` decimals, represented as an [`I256`] scaled by `10^D`.
+///
+/// Note: `D` must not exceed 76, the largest power of ten representable in an [`I256`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IFixed<const D: u8>(I256);
+
+impl<const D: u8> IFixed<D> {
+    /// The fixed-point representation of zero.
+    pub const ZERO: Self = Self(I256::ZERO);
+
+    /// Creates a fixed-point value from its scaled integer representation.
+    pub const fn from_raw(raw: I256) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the scaled integer representation.
+    pub const fn into_raw(self) -> I256 {
+        self.0
+    }
+
+    /// The factor by which values are scaled, `10^D`.
+    pub fn scale() -> I256 {
+        I256::from_raw(UFixed::<D>::scale())
+    }
+
+    /// Creates a fixed-point value from a whole number, if representable.
+    pub fn from_integer(value: I256) -> Option<Self> {
+        value.checked_mul(Self::scale()).map(Self)
+    }
+
+    /// Returns the whole-number part, rounding toward zero.
+    pub fn to_integer(self) -> I256 {
+        self.0 / Self::scale()
+    }
+
+    /// Returns `true` if the value is less than zero.
+    pub fn is_negative(self) -> bool {
+        self.0.is_negative()
+    }
+
+    /// Checked addition. Returns `None` on overflow.
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.0.checked_add(rhs.0).map(Self)
+    }
+
+    /// Checked subtraction. Returns `None` on overflow.
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).map(Self)
+    }
+
+    /// Scale-preserving multiplication, rounding toward zero. Returns `None` on overflow.
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        signed_mul_div(self.0, rhs.0, Self::scale()).map(Self)
+    }
+
+    /// Scale-preserving division, rounding toward zero.
+    /// Returns `None` on overflow or division by zero.
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        signed_mul_div(self.0, Self::scale(), rhs.0).map(Self)
+    }
+}
+
+impl<const D: u8> Add for IFixed<D> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs)
+            .expect("fixed-point addition overflow")
+    }
+}
+
+impl<const D: u8> Sub for IFixed<D> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs)
+            .expect("fixed-point subtraction overflow")
+    }
+}
+
+impl<const D: u8> Mul for IFixed<D> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.checked_mul(rhs)
+            .expect("fixed-point multiplication overflow")
+    }
+}
+
+impl<const D: u8> Div for IFixed<D> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.checked_div(rhs).expect("fixed-point division failed")
+    }
+}
+
+impl<const D: u8> Neg for IFixed<D> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        let raw = self.0.checked_neg().expect("fixed-point negation overflow");
+        Self(raw)
+    }
+}
+
+/// Accessor for a storage-backed [`IFixed`].
+///
+/// The value is stored as its scaled, two's-complement integer representation in a full word,
+/// exactly as a [`StorageI256`] would store it.
+#[derive(Debug)]
+pub struct StorageIFixed<const D: u8> {
+    inner: StorageI256,
+}
+
+impl<const D: u8> StorageIFixed<D> {
+    /// Gets the underlying [`IFixed`] in persistent storage.
+    pub fn get(&self) -> IFixed<D> {
+        IFixed::from_raw(self.inner.get())
+    }
+
+    /// Sets the underlying [`IFixed`] in persistent storage.
+    pub fn set(&mut self, value: IFixed<D>) {
+        self.inner.set(value.into_raw());
+    }
+}
+
+impl<const D: u8> StorageType for StorageIFixed<D> {
+    type Wraps<'a> = IFixed<D>;
+    type WrapsMut<'a> = StorageGuardMut<'a, Self>;
+
+    unsafe fn new(slot: U256, offset: u8) -> Self {
+        Self {
+            inner: StorageI256::new(slot, offset),
+        }
+    }
+
+    fn load<'s>(self) -> Self::Wraps<'s> {
+        self.get()
+    }
+
+    fn load_mut<'s>(self) -> Self::WrapsMut<'s> {
+        StorageGuardMut::new(self)
+    }
+}
+
+impl<'a, const D: u8> SimpleStorageType<'a> for StorageIFixed<D> {
+    fn set_by_wrapped(&mut self, value: Self::Wraps<'a>) {
+        self.set(value);
+    }
+}
+
+impl<const D: u8> Erase for StorageIFixed<D> {
+    fn erase(&mut self) {
+        self.inner.erase();
+    }
+}
+
+impl<const D: u8> From<StorageIFixed<D>> for IFixed<D> {
+    fn from(value: StorageIFixed<D>) -> Self {
+        value.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IFixed, UFixed};
+    use alloy_primitives::{I256, U256};
+
+    type Fixed = UFixed<18>;
+
+    fn fixed(whole: u64) -> Fixed {
+        Fixed::from_integer(U256::from(whole)).unwrap()
+    }
+
+    #[test]
+    fn test_ufixed_arithmetic() {
+        let half = Fixed::from_raw(U256::from(5) * U256::from(10).pow(U256::from(17)));
+        assert_eq!(
+            fixed(3) * half,
+            Fixed::from_raw(U256::from(15) * U256::from(10).pow(U256::from(17)))
+        );
+        assert_eq!(fixed(3) / half, fixed(6));
+        assert_eq!(fixed(3) + fixed(4), fixed(7));
+        assert_eq!((fixed(7) - fixed(4)).to_integer(), U256::from(3));
+        assert_eq!(fixed(1).checked_div(Fixed::ZERO), None);
+        assert_eq!(fixed(1).checked_sub(fixed(2)), None);
+    }
+
+    #[test]
+    fn test_ufixed_wide_intermediate() {
+        // the product of the raw values overflows 256 bits, but the scaled result does not
+        let big = Fixed::from_raw(U256::MAX / U256::from(2));
+        assert_eq!(big * fixed(1), big);
+        assert_eq!(big.checked_mul(fixed(3)), None);
+    }
+
+    #[test]
+    fn test_ifixed_arithmetic() {
+        type Signed = IFixed<6>;
+        let int = |x: i64| Signed::from_integer(I256::try_from(x).unwrap()).unwrap();
+        let raw = |x: i64| Signed::from_raw(I256::try_from(x).unwrap());
+        let quarter = raw(250_000);
+
+        assert_eq!(int(2) - int(5), int(-3));
+        assert_eq!(-int(3) * quarter, raw(-750_000));
+        assert_eq!(int(-3) / -quarter, int(12));
+        assert_eq!((int(-7) / int(2)).to_integer(), I256::try_from(-3).unwrap());
+        assert!(int(-1).is_negative());
+        assert_eq!(Signed::from_raw(I256::MIN).checked_sub(int(1)), None);
+        assert_eq!(int(1).checked_div(Signed::ZERO), None);
+    }
+}
@@ -0,0 +1,86 @@
+// Copyright 2023-2024, Offchain Labs, Inc.
+// For licensing, see https://github.com/OffchainLabs/stylus-sdk-rs/blob/main/licenses/COPYRIGHT.md
+
+use super::{SimpleStorageType, StorageGuard, StorageGuardMut, StorageType, StorageVec};
+use alloy_primitives::U256;
+
+/// Accessor for a storage-backed, append-only log, such as an audit trail or fill history.
+///
+/// The log is laid out exactly like a [`StorageVec`], and so like a Solidity dynamic array,
+/// but only supports [`append`](Self::append) and indexed reads. Since entries are never
+/// removed or modified, appending just writes the new entry and bumps the length.
+pub struct StorageLog<S: StorageType> {
+    entries: StorageVec<S>,
+}
+
+impl<S: StorageType> StorageType for StorageLog<S> {
+    type Wraps<'a> = StorageGuard<'a, StorageLog<S>> where Self: 'a;
+    type WrapsMut<'a> = StorageGuardMut<'a, StorageLog<S>> where Self: 'a;
+
+    unsafe fn new(slot: U256, offset: u8) -> Self {
+        Self {
+            entries: StorageVec::new(slot, offset),
+        }
+    }
+
+    fn load<'s>(self) -> Self::Wraps<'s> {
+        StorageGuard::new(self)
+    }
+
+    fn load_mut<'s>(self) -> Self::WrapsMut<'s> {
+        StorageGuardMut::new(self)
+    }
+
+    fn slot_count(&self) -> usize {
+        self.entries.slot_count()
+    }
+}
+
+impl<S: StorageType> StorageLog<S> {
+    /// Returns `true` if nothing has been appended.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Gets the number of entries appended.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+impl<'a, S: SimpleStorageType<'a>> StorageLog<S> {
+    /// Gets the entry at the given index, if it exists.
+    pub fn get(&self, index: usize) -> Option<S::Wraps<'a>> {
+        let store = self.entries.getter(index)?;
+        Some(unsafe { store.into_raw() }.into())
+    }
+
+    /// Adds an entry to the end of the log.
+    ///
+    /// # Panics
+    ///
+    /// If the log's length would overflow.
+    pub fn append(&mut self, value: S::Wraps<'a>) {
+        self.entries.push(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::StorageU64;
+    use alloy_primitives::U64;
+
+    #[test]
+    fn test_log() {
+        let mut log: StorageLog<StorageU64> = unsafe { StorageLog::new(U256::ZERO, 0) };
+        assert!(log.is_empty());
+        log.append(U64::from(1));
+        log.append(U64::from(2));
+        assert_eq!(log.len(), 2);
+        assert_eq!((log.get(1), log.get(2)), (Some(U64::from(2)), None));
+
+        let vec: StorageVec<StorageU64> = unsafe { StorageVec::new(U256::ZERO, 0) };
+        assert_eq!(vec.get(0_usize), Some(U64::from(1)));
+    }
+}
@@ -3,9 +3,12 @@
 
 use crate::crypto;
 
-use super::{Erase, SimpleStorageType, StorageGuard, StorageGuardMut, StorageType};
+use super::{
+    Erase, GlobalStorage, SimpleStorageType, Storage, StorageGuard, StorageGuardMut, StorageType,
+};
 use alloc::{string::String, vec::Vec};
 use alloy_primitives::{Address, FixedBytes, Signed, Uint, B256, U160, U256};
+use alloy_sol_types::SolValue;
 use core::marker::PhantomData;
 
 /// Accessor for a storage-backed map.
@@ -81,6 +84,22 @@ where
         store.set_by_wrapped(value);
     }
 
+    /// Sets each of the given entries, overwriting what may have been there.
+    /// This is useful for seeding many values at once, such as in an initializer.
+    pub fn set_many(&mut self, entries: impl IntoIterator<Item = (K, V::Wraps<'a>)>) {
+        for (key, value) in entries {
+            self.insert(key, value);
+        }
+    }
+
+    /// Gets the elements at each of the given keys, in order, with the zero-value for any
+    /// key that's absent. This is the read counterpart to [`set_many`](Self::set_many).
+    pub fn get_many(&self, keys: impl IntoIterator<Item = K>) -> Vec<V::Wraps<'a>> {
+        keys.into_iter()
+            .map(|key| unsafe { self.getter(key).into_raw() }.into())
+            .collect()
+    }
+
     /// Replace the element at the given key.
     /// Returns the old element, or the zero-value if none was there.
     pub fn replace(&mut self, key: K, value: V::Wraps<'a>) -> V::Wraps<'a> {
@@ -108,6 +127,102 @@ where
             value
         }
     }
+
+    /// Exchanges the elements at two keys. Does nothing if both keys map to the same slot.
+    pub fn swap(&mut self, a: K, b: K) {
+        let a = a.to_slot(self.slot.into());
+        let b = b.to_slot(self.slot.into());
+        if a == b {
+            return;
+        }
+        // intentionally alias so that we can write back after load
+        unsafe {
+            let mut store_a = V::new(a, Self::CHILD_OFFSET);
+            let mut store_b = V::new(b, Self::CHILD_OFFSET);
+            let value_a = V::new(a, Self::CHILD_OFFSET).load();
+            let value_b = V::new(b, Self::CHILD_OFFSET).load();
+            store_a.set_by_wrapped(value_b);
+            store_b.set_by_wrapped(value_a);
+        }
+    }
+
+    /// Gets the element at the given key, first inserting the result of `f` if the slot is zero.
+    /// This is useful for lazily initializing values on first use.
+    ///
+    /// Note: persistent storage can't distinguish an absent key from one holding the zero-value,
+    /// so `f` is called whenever the slot is zero, including after a zero was explicitly stored.
+    pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce() -> V::Wraps<'a>) -> V::Wraps<'a> {
+        let slot = key.to_slot(self.slot.into());
+        let mut store = unsafe { V::new(slot, Self::CHILD_OFFSET) };
+        if Storage::get_word(slot) == B256::ZERO {
+            store.set_by_wrapped(f());
+        }
+        store.load()
+    }
+
+    /// Gets the given key's corresponding entry for in-place manipulation,
+    /// like [`std::collections::HashMap::entry`][hashmap_entry].
+    ///
+    /// Note: persistent storage can't distinguish an absent key from one holding the zero-value,
+    /// so an entry is considered occupied only when its slot is nonzero. For value types where
+    /// zero is meaningful, an explicitly stored zero will appear vacant.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stylus_sdk::storage::{StorageMap, StorageType, StorageU256};
+    /// use stylus_sdk::alloy_primitives::{Address, U256};
+    ///
+    /// let mut counts: StorageMap<Address, StorageU256> = unsafe { StorageMap::new(U256::ZERO, 0) };
+    /// counts
+    ///     .entry(Address::ZERO)
+    ///     .and_modify(|count| count.set(count.get() + U256::from(1)))
+    ///     .or_insert(U256::from(1));
+    /// ```
+    ///
+    /// [hashmap_entry]: https://doc.rust-lang.org/std/collections/struct.HashMap.html#method.entry
+    pub fn entry(&mut self, key: K) -> StorageEntry<'_, V> {
+        let slot = key.to_slot(self.slot.into());
+        let occupied = Storage::get_word(slot) != B256::ZERO;
+        let store = unsafe { StorageGuardMut::new(V::new(slot, Self::CHILD_OFFSET)) };
+        StorageEntry { store, occupied }
+    }
+}
+
+/// A view into a single entry of a [`StorageMap`], obtained via [`StorageMap::entry`].
+pub struct StorageEntry<'a, V: StorageType> {
+    store: StorageGuardMut<'a, V>,
+    occupied: bool,
+}
+
+impl<'s, 'a, V: SimpleStorageType<'a>> StorageEntry<'s, V> {
+    /// Returns `true` if the entry's slot holds a nonzero value.
+    pub fn is_occupied(&self) -> bool {
+        self.occupied
+    }
+
+    /// Ensures a value is in the entry by inserting `default` if vacant.
+    /// Returns a mutable accessor to the entry's value.
+    pub fn or_insert(self, default: V::Wraps<'a>) -> StorageGuardMut<'s, V> {
+        self.or_insert_with(|| default)
+    }
+
+    /// Ensures a value is in the entry by inserting the result of `f` if vacant.
+    /// Returns a mutable accessor to the entry's value.
+    pub fn or_insert_with(mut self, f: impl FnOnce() -> V::Wraps<'a>) -> StorageGuardMut<'s, V> {
+        if !self.occupied {
+            self.store.set_by_wrapped(f());
+        }
+        self.store
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any potential inserts.
+    pub fn and_modify(mut self, f: impl FnOnce(&mut V)) -> Self {
+        if self.occupied {
+            f(&mut self.store);
+        }
+        self
+    }
 }
 
 impl<K, V> StorageMap<K, V>
@@ -198,6 +313,22 @@ impl StorageKey for bool {
     }
 }
 
+/// Composite keys, flattened into a single level of hashing.
+///
+/// The pair is first hashed as `keccak256(abi.encode(a, b))`, and the resulting `bytes32` is
+/// used as the key. This matches the common Solidity pattern of a `mapping(bytes32 => ...)`
+/// indexed by a manually-computed composite key.
+///
+/// Note: this is a different slot layout than a nested [`StorageMap<A, StorageMap<B, V>>`],
+/// which corresponds to Solidity's `mapping(A => mapping(B => ...))`. Choose whichever matches
+/// the contract whose storage is being accessed; the two are not interchangeable.
+impl<A: SolValue, B: SolValue> StorageKey for (A, B) {
+    fn to_slot(&self, root: B256) -> U256 {
+        let key = crypto::keccak(self.abi_encode_params());
+        key.to_slot(root)
+    }
+}
+
 macro_rules! impl_key {
     ($($uint:ident $int:ident)+) => {
         $(
@@ -221,3 +352,135 @@ macro_rules! impl_key {
 }
 
 impl_key!(u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 usize isize);
+
+#[cfg(test)]
+mod tests {
+    use super::{StorageKey, StorageMap};
+    use crate::{
+        crypto,
+        storage::{
+            StorageAddress, StorageBool, StorageGuard, StorageGuardMut, StorageType, StorageU256,
+            StorageU64,
+        },
+    };
+    use alloy_primitives::{b256, Address, B256, U256, U64};
+
+    /// A two-word struct, laid out as the `storage` macro would lay it out.
+    struct Position {
+        owner: StorageAddress,
+        paused: StorageBool,
+        balance: StorageU256,
+    }
+
+    impl StorageType for Position {
+        type Wraps<'a> = StorageGuard<'a, Self>;
+        type WrapsMut<'a> = StorageGuardMut<'a, Self>;
+
+        const REQUIRED_SLOTS: usize = 2;
+
+        unsafe fn new(slot: U256, offset: u8) -> Self {
+            debug_assert!(offset == 0);
+            Self {
+                owner: StorageAddress::new(slot, 12),
+                paused: StorageBool::new(slot, 11),
+                balance: StorageU256::new(slot + U256::from(1), 0),
+            }
+        }
+
+        fn load<'s>(self) -> Self::Wraps<'s> {
+            StorageGuard::new(self)
+        }
+
+        fn load_mut<'s>(self) -> Self::WrapsMut<'s> {
+            StorageGuardMut::new(self)
+        }
+    }
+
+    #[test]
+    fn test_set_many() {
+        let mut map: StorageMap<u8, StorageU64> = unsafe { StorageMap::new(U256::ZERO, 0) };
+        map.set_many([(1, U64::from(10)), (2, U64::from(20)), (1, U64::from(11))]);
+        assert_eq!(map.get(1), U64::from(11));
+        assert_eq!(map.get(2), U64::from(20));
+        assert_eq!(map.get(3), U64::ZERO);
+    }
+
+    #[test]
+    fn test_get_many() {
+        let mut map: StorageMap<u8, StorageU64> = unsafe { StorageMap::new(U256::ZERO, 0) };
+        map.set_many([(1, U64::from(10)), (2, U64::from(20))]);
+        let values = map.get_many([2, 3, 1]);
+        assert_eq!(values, [U64::from(20), U64::ZERO, U64::from(10)]);
+    }
+
+    #[test]
+    fn test_get_or_insert_with() {
+        let mut map: StorageMap<u8, StorageU64> = unsafe { StorageMap::new(U256::ZERO, 0) };
+        assert_eq!(map.get_or_insert_with(1, || U64::from(10)), U64::from(10));
+        assert_eq!(map.get_or_insert_with(1, || unreachable!()), U64::from(10));
+        assert_eq!(map.get(1), U64::from(10));
+    }
+
+    #[test]
+    fn test_swap() {
+        let mut map: StorageMap<u8, StorageU64> = unsafe { StorageMap::new(U256::ZERO, 0) };
+        map.set_many([(1, U64::from(10)), (2, U64::from(20))]);
+        map.swap(1, 2);
+        map.swap(3, 3);
+        assert_eq!((map.get(1), map.get(2)), (U64::from(20), U64::from(10)));
+
+        map.swap(2, 4);
+        assert_eq!((map.get(2), map.get(4)), (U64::ZERO, U64::from(10)));
+    }
+
+    #[test]
+    fn test_unset_struct_key() {
+        let mut map: StorageMap<U256, Position> = unsafe { StorageMap::new(U256::ZERO, 0) };
+        let mut set = map.setter(U256::from(1));
+        set.owner.set(Address::repeat_byte(0x11));
+        set.paused.set(true);
+        set.balance.set(U256::MAX);
+
+        let unset = map.getter(U256::from(2));
+        assert_eq!(unset.owner.get(), Address::ZERO);
+        assert!(!unset.paused.get());
+        assert_eq!(unset.balance.get(), U256::ZERO);
+        assert_eq!(map.getter(U256::from(1)).balance.get(), U256::MAX);
+    }
+
+    #[test]
+    fn test_pair_key_slot() {
+        let root = B256::from(U256::from(7));
+        let owner = Address::repeat_byte(0x11);
+        let id = U256::from(42);
+
+        let mut encoded = B256::left_padding_from(owner.as_slice()).to_vec();
+        encoded.extend(B256::from(id));
+        let composite = crypto::keccak(encoded);
+        let expected: U256 = crypto::keccak(composite.concat_const::<32, 64>(root)).into();
+
+        assert_eq!((owner, id).to_slot(root), expected);
+        assert_ne!(
+            (owner, id).to_slot(root),
+            id.to_slot(owner.to_slot(root).into())
+        );
+    }
+
+    #[test]
+    fn test_solidity_slots() {
+        // `mapping(uint256 => uint256)` at slot 0, as computed by `keccak256(abi.encode(k, 0))`
+        let root = B256::ZERO;
+        let zero = b256!("ad3228b676f7d3cd4284a5443f17f1962b36e491b30a40b2405849e597ba5fb5");
+        let one = b256!("ada5013122d395ba3c54772283fb069b10426056ef8ca54750cb9bb552a59e7d");
+        assert_eq!(U256::ZERO.to_slot(root), U256::from_be_bytes(zero.0));
+        assert_eq!(U256::from(1).to_slot(root), U256::from_be_bytes(one.0));
+
+        // value types are left-padded to a word, while `bytes` and `string` keys aren't padded
+        let owner = Address::repeat_byte(0x11);
+        let word = B256::left_padding_from(owner.as_slice());
+        assert_eq!(owner.to_slot(root), word.to_slot(root));
+        let bytes = vec![0x11; 20];
+        let expected: U256 = crypto::keccak([&bytes[..], root.as_slice()].concat()).into();
+        assert_eq!(bytes.to_slot(root), expected);
+    }
+}
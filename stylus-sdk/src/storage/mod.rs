@@ -25,35 +25,141 @@
 use crate::hostio;
 use alloy_primitives::{Address, BlockHash, BlockNumber, FixedBytes, Signed, Uint, B256, U256};
 use alloy_sol_types::sol_data::{ByteCount, SupportedFixedBytes};
-use core::{cell::OnceCell, marker::PhantomData, ops::Deref};
+use core::{
+    cell::OnceCell,
+    marker::PhantomData,
+    ops::Deref,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
+pub use address_flag::StorageAddressFlag;
 pub use array::StorageArray;
+pub use bitset::{StorageBitMap, StorageBitSet};
 pub use bytes::{StorageBytes, StorageString};
-pub use map::{StorageKey, StorageMap};
+pub use counted_map::CountedStorageMap;
+pub use enums::{StorageEnum, StorageEnumValue};
+pub use error::StorageError;
+pub use fixed::{IFixed, StorageIFixed, StorageUFixed, UFixed};
+pub use log::StorageLog;
+pub use map::{StorageEntry, StorageKey, StorageMap};
+pub use ratio::StorageRatio;
+pub use small_vec::StorageSmallVec;
+pub use sorted_map::StorageSortedMap;
+pub use time_amount::StorageTimeAmount;
 pub use traits::{
     Erase, GlobalStorage, SimpleStorageType, StorageGuard, StorageGuardMut, StorageType,
     TopLevelStorage,
 };
-pub use vec::StorageVec;
+pub use transient::{TransientBool, TransientStorage, TransientU256};
+pub use vec::{StorageVec, StorageVecIter, StorageVecIterMut};
 
+mod address_flag;
 mod array;
+mod bitset;
 mod bytes;
+mod counted_map;
+mod enums;
+mod error;
+mod fixed;
+mod log;
 mod map;
+mod newtype;
+mod ratio;
+mod small_vec;
+mod sorted_map;
+mod time_amount;
 mod traits;
+mod transient;
 mod vec;
 
+// the backend defines hostio symbols, so it must never be linked into a contract
+#[cfg(all(any(test, feature = "storage-test"), not(target_arch = "wasm32")))]
+pub mod testing;
+
+#[cfg(feature = "trace-storage")]
+pub mod trace;
+
 pub(crate) type Storage = StorageCache;
 
+/// Records the bytes a struct field occupies, so that fields of distinct structs accidentally
+/// laid out over the same storage can be caught in tests. Called by the [`storage`] macro.
+///
+/// Since contracts don't run with the test backend, this is a no-op unless the `storage-test`
+/// feature flag is enabled in a native build with debug assertions.
+///
+/// [`storage`]: macro@stylus_proc::storage
+#[doc(hidden)]
+#[allow(unused_variables)]
+pub fn claim_field(field: &'static str, slot: U256, offset: u8, bytes: usize) {
+    #[cfg(all(
+        debug_assertions,
+        any(test, feature = "storage-test"),
+        not(target_arch = "wasm32")
+    ))]
+    testing::claim(field, slot, offset, bytes);
+}
+
+/// Records the words a composite struct field spans, then constructs its accessor. Unlike
+/// [`claim_field`], the fields of nested structs may lie within these words.
+/// Called by the [`storage`] macro.
+///
+/// [`storage`]: macro@stylus_proc::storage
+#[doc(hidden)]
+#[allow(unused_variables)]
+pub fn claim_composite<S>(
+    field: &'static str,
+    slot: U256,
+    words: usize,
+    new: impl FnOnce() -> S,
+) -> S {
+    #[cfg(all(
+        debug_assertions,
+        any(test, feature = "storage-test"),
+        not(target_arch = "wasm32")
+    ))]
+    testing::claim_words(field, slot, words);
+    nest(new)
+}
+
+// when tracking claims, composites note that their contents are nested within them
+#[cfg(all(
+    debug_assertions,
+    any(test, feature = "storage-test"),
+    not(target_arch = "wasm32")
+))]
+use testing::nest;
+
+/// Constructs the contents of a composite accessor, so that the fields of structs within it
+/// aren't mistaken for collisions with the composite's own claim.
+#[cfg(not(all(
+    debug_assertions,
+    any(test, feature = "storage-test"),
+    not(target_arch = "wasm32")
+)))]
+#[inline]
+fn nest<S>(new: impl FnOnce() -> S) -> S {
+    new()
+}
+
 /// Global accessor to persistent storage that relies on VM-level caching.
 ///
+/// Note: because the cache lives in the VM rather than in the program, there is no way to
+/// observe whether a given slot is already cached (warm) without accessing it.
+/// Likewise, cached slots don't occupy the program's own memory, so there's no capacity to
+/// reserve up front. Storage-heavy entry points can instead [`flush`](Self::flush) periodically.
+///
 /// [`LocalStorageCache`]: super::LocalStorageCache
 pub struct StorageCache;
 
 impl GlobalStorage for StorageCache {
     /// Retrieves a 32-byte EVM word from persistent storage.
     fn get_word(key: U256) -> B256 {
-        let mut data = B256::ZERO;
-        unsafe { hostio::storage_load_bytes32(B256::from(key).as_ptr(), data.as_mut_ptr()) };
+        let data = Self::load_word(key);
+        #[cfg(feature = "trace-storage")]
+        trace::record(|| trace::StorageAccess::Load {
+            slot: key,
+            value: data,
+        });
         data
     }
 
@@ -63,24 +169,93 @@ impl GlobalStorage for StorageCache {
     ///
     /// May alias storage.
     unsafe fn set_word(key: U256, value: B256) {
+        // the prior value is only read if a tracer is installed
+        #[cfg(feature = "trace-storage")]
+        trace::record(|| trace::StorageAccess::Store {
+            slot: key,
+            old: Self::load_word(key),
+            new: value,
+        });
+        Self::invalidate();
         hostio::storage_cache_bytes32(B256::from(key).as_ptr(), value.as_ptr())
     }
 }
 
+/// Counts writes to persistent storage and clears of the VM cache. See [`StorageCache::epoch`].
+static EPOCH: AtomicUsize = AtomicUsize::new(0);
+
 impl StorageCache {
+    /// A counter that advances whenever storage is written, flushed, or cleared. Accessors
+    /// that cache bookkeeping, like [`StorageVec`]'s length, record the epoch to tell when it may
+    /// have been changed through another accessor or by a reentrant call.
+    pub(crate) fn epoch() -> usize {
+        EPOCH.load(Ordering::Relaxed)
+    }
+
+    /// Advances the [`epoch`](Self::epoch).
+    fn invalidate() {
+        EPOCH.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Reads a word via the VM cache, without tracing.
+    fn load_word(key: U256) -> B256 {
+        let mut data = B256::ZERO;
+        unsafe { hostio::storage_load_bytes32(B256::from(key).as_ptr(), data.as_mut_ptr()) };
+        data
+    }
+
     /// Flushes the VM cache, persisting all values to the EVM state trie.
     /// Note: this is used at the end of the [`entrypoint`] macro and is not typically called by user code.
     ///
+    /// Since dirty values are held by the VM rather than in program memory, deferring writes
+    /// doesn't grow the program's heap. The VM doesn't report how many slots are dirty, so
+    /// there's no way to flush conditionally on the size of the cache.
+    ///
     /// [`entrypoint`]: macro@stylus_proc::entrypoint
     pub fn flush() {
+        Self::invalidate();
         unsafe { hostio::storage_flush_cache(false) }
     }
 
     /// Flushes and clears the VM cache, persisting all values to the EVM state trie.
     /// This is useful in cases of reentrancy to ensure cached values from one call context show up in another.
+    ///
+    /// After an external call that may have written to this program's storage, clearing ensures
+    /// that subsequent reads are served from the EVM state trie rather than stale cache entries.
+    /// Note that accessors keep the values they've already read, so re-create them after the call.
     pub fn clear() {
+        Self::invalidate();
         unsafe { hostio::storage_flush_cache(true) }
     }
+
+    /// Stores a word only if it differs from the current value, returning whether it was written.
+    ///
+    /// Since caching happens in the VM, the comparison costs a read, which is warm if the slot
+    /// has already been accessed. Skipping the write keeps an idempotent setter from dirtying
+    /// the slot in the VM's cache.
+    ///
+    /// # Safety
+    ///
+    /// Aliases if called during the lifetime an overlapping accessor.
+    pub unsafe fn set_word_if_changed(key: U256, value: B256) -> bool {
+        if Self::get_word(key) == value {
+            return false;
+        }
+        Self::set_word(key, value);
+        true
+    }
+
+    /// Writes each `(slot, value)` pair to the VM cache, to be persisted by the next flush.
+    /// This is useful for applying a state delta computed off-chain, such as during a migration.
+    ///
+    /// # Safety
+    ///
+    /// Aliases any accessors over the given slots, whose values may become stale.
+    pub unsafe fn apply(diff: &[(U256, B256)]) {
+        for (key, value) in diff {
+            Self::set_word(*key, *value);
+        }
+    }
 }
 
 /// Overwrites the value in a cell.
@@ -153,11 +328,36 @@ impl<const B: usize, const L: usize> StorageUint<B, L> {
         **self
     }
 
+    /// Gets the underlying [`alloy_primitives::Uint`] in persistent storage, or `default` if it's zero.
+    /// Note: persistent storage can't distinguish an unset value from an explicitly stored zero.
+    pub fn get_or(&self, default: Uint<B, L>) -> Uint<B, L> {
+        match self.get() {
+            value if value.is_zero() => default,
+            value => value,
+        }
+    }
+
+    /// Gets the underlying [`alloy_primitives::Uint`] in persistent storage as a [`usize`],
+    /// or `None` if it doesn't fit. This is useful for stored lengths and indices, which
+    /// would otherwise be silently truncated on 32-bit targets like `wasm32`.
+    pub fn get_usize(&self) -> Option<usize> {
+        self.get().try_into().ok()
+    }
+
     /// Sets the underlying [`alloy_primitives::Uint`] in persistent storage.
     pub fn set(&mut self, value: Uint<B, L>) {
         overwrite_cell(&mut self.cached, value);
         unsafe { Storage::set_uint(self.slot, self.offset.into(), value) };
     }
+
+    /// Subtracts `amount` from the underlying value, such as when debiting a balance,
+    /// returning the new value. Storage is left unchanged if the value is less than `amount`.
+    pub fn try_sub(&mut self, amount: Uint<B, L>) -> Result<Uint<B, L>, StorageError> {
+        let value = self.get().checked_sub(amount);
+        let value = value.ok_or(StorageError::InsufficientBalance)?;
+        self.set(value);
+        Ok(value)
+    }
 }
 
 impl<const B: usize, const L: usize> StorageType for StorageUint<B, L> {
@@ -229,6 +429,15 @@ impl<const B: usize, const L: usize> StorageSigned<B, L> {
         **self
     }
 
+    /// Gets the underlying [`Signed`] in persistent storage, or `default` if it's zero.
+    /// Note: persistent storage can't distinguish an unset value from an explicitly stored zero.
+    pub fn get_or(&self, default: Signed<B, L>) -> Signed<B, L> {
+        match self.get() {
+            value if value.is_zero() => default,
+            value => value,
+        }
+    }
+
     /// Gets the underlying [`Signed`] in persistent storage.
     pub fn set(&mut self, value: Signed<B, L>) {
         overwrite_cell(&mut self.cached, value);
@@ -287,6 +496,13 @@ impl<const B: usize, const L: usize> From<StorageSigned<B, L>> for Signed<B, L>
 }
 
 /// Accessor for a storage-backed [`FixedBytes`].
+///
+/// The bytes occupy `N` consecutive bytes of the word, in order, starting `offset` bytes from
+/// the left. Since the [`storage`](macro@stylus_proc::storage) macro packs fields from the
+/// right, a `bytesN` alone in its slot sits in the word's rightmost `N` bytes, which is exactly
+/// where Solidity stores it. Solidity's left alignment of `bytesN` applies to ABI encoding and
+/// memory, not to storage. To match a custom layout instead, construct the accessor directly
+/// with the desired offset, such as `0` for the leftmost bytes.
 #[derive(Debug)]
 pub struct StorageFixedBytes<const N: usize> {
     slot: U256,
@@ -300,6 +516,15 @@ impl<const N: usize> StorageFixedBytes<N> {
         **self
     }
 
+    /// Gets the underlying [`FixedBytes`] in persistent storage, or `default` if it's zero.
+    /// Note: persistent storage can't distinguish an unset value from an explicitly stored zero.
+    pub fn get_or(&self, default: FixedBytes<N>) -> FixedBytes<N> {
+        match self.get() {
+            value if value.is_zero() => default,
+            value => value,
+        }
+    }
+
     /// Gets the underlying [`FixedBytes`] in persistent storage.
     pub fn set(&mut self, value: FixedBytes<N>) {
         overwrite_cell(&mut self.cached, value);
@@ -385,6 +610,23 @@ impl StorageBool {
         overwrite_cell(&mut self.cached, value);
         unsafe { Storage::set_byte(self.slot, self.offset.into(), value as u8) }
     }
+
+    /// Flips the underlying [`bool`] in persistent storage, returning the new value.
+    pub fn toggle(&mut self) -> bool {
+        let value = !self.get();
+        self.set(value);
+        value
+    }
+
+    /// Sets the underlying [`bool`] to `new` only if it currently equals `expected`,
+    /// returning whether it was set. This is useful for flags guarding one-time operations.
+    pub fn compare_and_set(&mut self, expected: bool, new: bool) -> bool {
+        if self.get() != expected {
+            return false;
+        }
+        self.set(new);
+        true
+    }
 }
 
 impl StorageType for StorageBool {
@@ -453,6 +695,15 @@ impl StorageAddress {
         **self
     }
 
+    /// Gets the underlying [`Address`] in persistent storage, or `default` if it's zero.
+    /// Note: persistent storage can't distinguish an unset value from an explicitly stored zero.
+    pub fn get_or(&self, default: Address) -> Address {
+        match self.get() {
+            value if value.is_zero() => default,
+            value => value,
+        }
+    }
+
     /// Gets the underlying [`Address`] in persistent storage.
     pub fn set(&mut self, value: Address) {
         overwrite_cell(&mut self.cached, value);
@@ -527,6 +778,15 @@ impl StorageBlockNumber {
         **self
     }
 
+    /// Gets the underlying [`BlockNumber`] in persistent storage, or `default` if it's zero.
+    /// Note: persistent storage can't distinguish an unset value from an explicitly stored zero.
+    pub fn get_or(&self, default: BlockNumber) -> BlockNumber {
+        match self.get() {
+            0 => default,
+            value => value,
+        }
+    }
+
     /// Sets the underlying [`BlockNumber`] in persistent storage.
     pub fn set(&mut self, value: BlockNumber) {
         overwrite_cell(&mut self.cached, value);
@@ -603,6 +863,15 @@ impl StorageBlockHash {
         **self
     }
 
+    /// Gets the underlying [`BlockHash`] in persistent storage, or `default` if it's zero.
+    /// Note: persistent storage can't distinguish an unset value from an explicitly stored zero.
+    pub fn get_or(&self, default: BlockHash) -> BlockHash {
+        match self.get() {
+            value if value.is_zero() => default,
+            value => value,
+        }
+    }
+
     /// Sets the underlying [`BlockHash`] in persistent storage.
     pub fn set(&mut self, value: BlockHash) {
         overwrite_cell(&mut self.cached, value);
@@ -680,3 +949,286 @@ impl<T> StorageType for PhantomData<T> {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, b256, fixed_bytes, Uint, I16, I256, U16, U256, U64, U8};
+
+    #[test]
+    fn test_uint_round_trips() {
+        testing::assert_round_trips::<StorageU8>([U8::ZERO, U8::from(0x7f), U8::MAX]);
+        testing::assert_round_trips::<StorageU16>([U16::from(0x1234), U16::MAX]);
+        testing::assert_round_trips::<StorageU64>([U64::from(0x0102030405060708_u64)]);
+        testing::assert_round_trips::<StorageUint<24, 1>>([Uint::from(0x123456)]);
+        testing::assert_round_trips::<StorageU256>([U256::ZERO, U256::from(1), U256::MAX]);
+    }
+
+    #[test]
+    fn test_signed_round_trips() {
+        testing::assert_round_trips::<StorageI256>([I256::ZERO, I256::MINUS_ONE, I256::MIN]);
+        testing::assert_round_trips::<StorageI8>([Signed::MINUS_ONE, Signed::MAX, Signed::MIN]);
+    }
+
+    #[test]
+    fn test_fixed_bytes_round_trips() {
+        testing::assert_round_trips::<StorageB8>([fixed_bytes!("01")]);
+        testing::assert_round_trips::<StorageB32>([fixed_bytes!("01020304")]);
+        testing::assert_round_trips::<StorageB160>([fixed_bytes!(
+            "0102030405060708090a0b0c0d0e0f1011121314"
+        )]);
+        testing::assert_round_trips::<StorageB256>([b256!(
+            "0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20"
+        )]);
+    }
+
+    #[test]
+    fn test_misc_round_trips() {
+        testing::assert_round_trips::<StorageBool>([true, false]);
+        testing::assert_round_trips::<StorageAddress>([
+            Address::ZERO,
+            address!("361594F5429D23ECE0A88E4fBE529E1c49D524d8"),
+        ]);
+        testing::assert_round_trips::<StorageBlockNumber>([0, 1, u64::MAX]);
+        testing::assert_round_trips::<StorageBlockHash>([BlockHash::repeat_byte(0x42)]);
+        testing::assert_round_trips::<StorageUFixed<18>>([UFixed::from_raw(U256::from(5))]);
+        testing::assert_round_trips::<StorageIFixed<18>>([IFixed::from_raw(I256::MINUS_ONE)]);
+    }
+
+    #[test]
+    fn test_zero_width_writes() {
+        testing::reset();
+        unsafe {
+            StorageCache::set::<0>(U256::ZERO, 0, FixedBytes::ZERO);
+            StorageCache::set_uint(U256::ZERO, 0, Uint::<0, 0>::ZERO);
+        }
+        assert_eq!(testing::pending_writes(), 0);
+    }
+
+    #[test]
+    fn test_get_words_packed() {
+        let slot = U256::from(3);
+        let mut word = B256::ZERO;
+        word[28..30].copy_from_slice(&[0x12, 0x34]);
+        word[31] = 0x56;
+        unsafe { StorageCache::set_word(slot, word) };
+
+        let fields = StorageCache::get_words_packed(slot, [(28, 2), (30, 1), (31, 1)]);
+        assert_eq!(fields, [U256::from(0x1234), U256::ZERO, U256::from(0x56)]);
+    }
+
+    #[test]
+    fn test_sub_word_uint_layout() {
+        type StorageU24 = StorageUint<24, 1>;
+        type StorageU40 = StorageUint<40, 1>;
+        testing::assert_round_trips::<StorageU24>([Uint::from(0x123456), Uint::MAX]);
+        testing::assert_round_trips::<StorageU40>([Uint::from(0x0102030405_u64)]);
+
+        let slot = U256::from(5);
+        unsafe { StorageU24::new(slot, 29) }.set(Uint::from(0x123456));
+        unsafe { StorageU40::new(slot, 0) }.set(Uint::from(0x0102030405_u64));
+        let word = StorageCache::get_word(slot);
+        assert_eq!(word[29..], [0x12, 0x34, 0x56]);
+        assert_eq!(word[..5], [0x01, 0x02, 0x03, 0x04, 0x05]);
+    }
+
+    #[test]
+    fn test_get_or() {
+        let mut fee = unsafe { StorageU16::new(U256::ZERO, 0) };
+        assert_eq!(fee.get_or(U16::from(30)), U16::from(30));
+        fee.set(U16::from(5));
+        assert_eq!(fee.get_or(U16::from(30)), U16::from(5));
+
+        let owner = unsafe { StorageAddress::new(U256::from(1), 0) };
+        let fallback = address!("361594F5429D23ECE0A88E4fBE529E1c49D524d8");
+        assert_eq!(owner.get_or(fallback), fallback);
+    }
+
+    #[test]
+    fn test_set_fields() {
+        let slot = U256::from(4);
+        unsafe { StorageCache::set_word(slot, B256::repeat_byte(0xff)) };
+        unsafe { StorageCache::set_fields(slot, &[(0, &[1, 2]), (31, &[3]), (1, &[4])]) };
+
+        let mut expected = B256::repeat_byte(0xff);
+        expected[..2].copy_from_slice(&[1, 4]);
+        expected[31] = 3;
+        assert_eq!(StorageCache::get_word(slot), expected);
+    }
+
+    #[test]
+    fn test_preload() {
+        testing::reset();
+        let word = B256::repeat_byte(0x11);
+        testing::preload(&[(U256::from(1), word), (U256::MAX, word)]);
+        assert_eq!(testing::pending_writes(), 0);
+        assert_eq!(StorageCache::get_word(U256::MAX), word);
+
+        let balance = unsafe { StorageU256::new(U256::from(1), 0) };
+        assert_eq!(balance.get(), U256::from_be_bytes(word.0));
+    }
+
+    #[test]
+    fn test_get_usize() {
+        let mut index = unsafe { StorageU256::new(U256::ZERO, 0) };
+        index.set(U256::from(7));
+        assert_eq!(index.get_usize(), Some(7));
+        index.set(U256::from(usize::MAX) + U256::from(1));
+        assert_eq!(index.get_usize(), None);
+
+        let small = unsafe { StorageU8::new(U256::from(1), 0) };
+        assert_eq!(small.get_usize(), Some(0));
+    }
+
+    #[test]
+    fn test_claim_field() {
+        testing::reset();
+        claim_field("Vault.owner", U256::ZERO, 12, 20);
+        claim_field("Vault.owner", U256::ZERO, 12, 20);
+        claim_field("Vault.paused", U256::ZERO, 11, 1);
+        claim_field("Token.owner", U256::from(1), 12, 20);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "storage collision: `Token.supply` overlaps `Vault.owner`")]
+    fn test_claim_field_collision() {
+        testing::reset();
+        claim_field("Vault.owner", U256::ZERO, 12, 20);
+        claim_field("Token.supply", U256::ZERO, 0, 32);
+    }
+
+    #[test]
+    fn test_claim_composite() {
+        testing::reset();
+        let construct = || {
+            claim_field("Vault.version", U256::ZERO, 24, 8);
+            claim_composite("Vault.config", U256::from(1), 2, || {
+                claim_field("Config.owner", U256::from(1), 12, 20);
+                claim_field("Config.limit", U256::from(2), 0, 32);
+            });
+            claim_composite("Vault.items", U256::from(3), 1, || ());
+        };
+        construct();
+        construct();
+
+        // elements of a composite, like those of an array, may be constructed later
+        nest(|| claim_field("Config.owner", U256::from(1), 12, 20));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "storage collision: `Token.supply` overlaps `Vault.items`")]
+    fn test_claim_composite_collision() {
+        testing::reset();
+        claim_composite("Vault.items", U256::from(3), 1, || ());
+        claim_field("Token.supply", U256::from(3), 0, 32);
+    }
+
+    #[test]
+    fn test_fixed_bytes_alignment() {
+        let selector = fixed_bytes!("a9059cbb");
+        let right = U256::from(6);
+        let left = U256::from(7);
+        unsafe { StorageB32::new(right, 28) }.set(selector);
+        unsafe { StorageB32::new(left, 0) }.set(selector);
+
+        let word = StorageCache::get_word(right);
+        assert_eq!(U256::from_be_bytes(word.0), U256::from(0xa9059cbb_u32));
+        assert_eq!(StorageCache::get_word(left)[..4], selector[..]);
+    }
+
+    #[test]
+    fn test_apply() {
+        testing::reset();
+        let word = B256::repeat_byte(0x22);
+        unsafe { StorageCache::apply(&[(U256::from(1), word), (U256::from(2), word)]) };
+        assert_eq!(testing::pending_writes(), 2);
+        assert_eq!(testing::persisted(U256::from(1)), B256::ZERO);
+
+        StorageCache::flush();
+        assert_eq!(testing::pending_writes(), 0);
+        assert_eq!(testing::persisted(U256::from(2)), word);
+    }
+
+    #[test]
+    fn test_set_word_if_changed() {
+        testing::reset();
+        let word = B256::repeat_byte(0x33);
+        testing::preload(&[(U256::ZERO, word)]);
+        assert!(!unsafe { StorageCache::set_word_if_changed(U256::ZERO, word) });
+        assert_eq!(testing::pending_writes(), 0);
+
+        assert!(unsafe { StorageCache::set_word_if_changed(U256::ZERO, B256::ZERO) });
+        assert_eq!(testing::pending_writes(), 1);
+    }
+
+    #[test]
+    fn test_try_sub() {
+        let mut balance = unsafe { StorageU256::new(U256::ZERO, 0) };
+        balance.set(U256::from(10));
+        assert_eq!(balance.try_sub(U256::from(4)), Ok(U256::from(6)));
+        let err = balance.try_sub(U256::from(7));
+        assert_eq!(err, Err(StorageError::InsufficientBalance));
+        assert_eq!(balance.get(), U256::from(6));
+        assert_eq!(balance.try_sub(U256::from(6)), Ok(U256::ZERO));
+    }
+
+    #[test]
+    fn test_bool_toggle_and_cas() {
+        let mut flag = unsafe { StorageBool::new(U256::ZERO, 3) };
+        assert!(flag.toggle());
+        assert_eq!(Storage::get_word(U256::ZERO)[3], 1);
+        assert!(!flag.toggle());
+
+        assert!(!flag.compare_and_set(true, false));
+        assert!(flag.compare_and_set(false, true));
+        assert!(!flag.compare_and_set(false, true));
+        assert!(unsafe { StorageBool::new(U256::ZERO, 3) }.get());
+    }
+
+    #[test]
+    fn test_bool_packing() {
+        // laid out like `struct { bool flag; address owner; uint8 tier; }`
+        let slot = U256::from(1);
+        let mut flag = unsafe { StorageBool::new(slot, 31) };
+        let mut owner = unsafe { StorageAddress::new(slot, 11) };
+        let mut tier = unsafe { StorageU8::new(slot, 10) };
+        let addr = address!("1111111111111111111111111111111111111111");
+        flag.set(true);
+        owner.set(addr);
+        tier.set(U8::from(0xff));
+        flag.set(false);
+        flag.set(true);
+
+        let word = Storage::get_word(slot);
+        assert_eq!((word[31], word[10], word[9]), (1, 0xff, 0));
+        assert_eq!(&word[11..31], addr.as_slice());
+        assert_eq!(unsafe { StorageAddress::new(slot, 11) }.get(), addr);
+
+        // as in Solidity, any nonzero byte reads as true
+        unsafe { Storage::set_byte(slot, 31, 2) };
+        assert!(unsafe { StorageBool::new(slot, 31) }.get());
+    }
+
+    #[test]
+    fn test_erase_packed() {
+        let slot = U256::from(1);
+        let mut low = unsafe { StorageU8::new(slot, 31) };
+        let mut high = unsafe { StorageU8::new(slot, 30) };
+        let mut signed = unsafe { StorageI16::new(slot, 28) };
+        let mut bytes = unsafe { StorageB32::new(slot, 24) };
+        low.set(U8::from(1));
+        high.set(U8::from(2));
+        signed.set(I16::MINUS_ONE);
+        bytes.set(fixed_bytes!("deadbeef"));
+
+        low.erase();
+        signed.erase();
+        let word = Storage::get_word(slot);
+        assert_eq!(&word[24..], &[0xde, 0xad, 0xbe, 0xef, 0, 0, 2, 0]);
+        bytes.erase();
+        high.erase();
+        assert_eq!(Storage::get_word(slot), B256::ZERO);
+    }
+}
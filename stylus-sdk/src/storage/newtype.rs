@@ -0,0 +1,120 @@
+// Copyright 2023-2024, Offchain Labs, Inc.
+// For licensing, see https://github.com/OffchainLabs/stylus-sdk-rs/blob/main/licenses/COPYRIGHT.md
+
+/// Generates an accessor for a domain-specific newtype, stored exactly like the type it wraps.
+///
+/// Given a tuple struct such as `Shares(U256)` and an existing accessor for its field, the macro
+/// defines a new accessor whose `get` and `set` speak in terms of the newtype. This keeps values
+/// like share and asset amounts distinct at compile time while sharing a storage layout.
+///
+/// The generated accessor forwards [`SLOT_BYTES`], [`REQUIRED_SLOTS`], and construction to the
+/// inner accessor, so it packs into structs and collections just as the inner type would.
+///
+/// ```no_run
+/// use stylus_sdk::alloy_primitives::U256;
+/// use stylus_sdk::storage::{StorageType, StorageU256};
+/// use stylus_sdk::storage_newtype;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// pub struct Shares(pub U256);
+///
+/// storage_newtype! {
+///     /// Accessor for a storage-backed [`Shares`].
+///     pub struct StorageShares(StorageU256) => Shares;
+/// }
+///
+/// let mut shares = unsafe { StorageShares::new(U256::ZERO, 0) };
+/// shares.set(Shares(U256::from(100)));
+/// ```
+///
+/// [`SLOT_BYTES`]: crate::storage::StorageType::SLOT_BYTES
+/// [`REQUIRED_SLOTS`]: crate::storage::StorageType::REQUIRED_SLOTS
+#[macro_export]
+macro_rules! storage_newtype {
+    ($(#[$meta:meta])* $vis:vis struct $name:ident($inner:ty) => $wraps:path;) => {
+        $(#[$meta])*
+        $vis struct $name {
+            inner: $inner,
+        }
+
+        impl $name {
+            #[doc = concat!("Gets the underlying [`", stringify!($wraps), "`] in persistent storage.")]
+            pub fn get(&self) -> $wraps {
+                $wraps(self.inner.get())
+            }
+
+            #[doc = concat!("Sets the underlying [`", stringify!($wraps), "`] in persistent storage.")]
+            pub fn set(&mut self, value: $wraps) {
+                self.inner.set(value.0);
+            }
+        }
+
+        impl $crate::storage::StorageType for $name {
+            type Wraps<'a> = $wraps;
+            type WrapsMut<'a> = $crate::storage::StorageGuardMut<'a, Self>;
+
+            const SLOT_BYTES: usize = <$inner as $crate::storage::StorageType>::SLOT_BYTES;
+            const REQUIRED_SLOTS: usize = <$inner as $crate::storage::StorageType>::REQUIRED_SLOTS;
+
+            unsafe fn new(slot: $crate::alloy_primitives::U256, offset: u8) -> Self {
+                Self {
+                    inner: <$inner as $crate::storage::StorageType>::new(slot, offset),
+                }
+            }
+
+            fn load<'s>(self) -> Self::Wraps<'s> {
+                self.get()
+            }
+
+            fn load_mut<'s>(self) -> Self::WrapsMut<'s> {
+                $crate::storage::StorageGuardMut::new(self)
+            }
+        }
+
+        impl<'a> $crate::storage::SimpleStorageType<'a> for $name {
+            fn set_by_wrapped(&mut self, value: Self::Wraps<'a>) {
+                self.set(value);
+            }
+        }
+
+        impl $crate::storage::Erase for $name {
+            fn erase(&mut self) {
+                $crate::storage::Erase::erase(&mut self.inner);
+            }
+        }
+
+        impl From<$name> for $wraps {
+            fn from(value: $name) -> Self {
+                value.get()
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::{testing, StorageType, StorageU64, StorageUint};
+    use alloy_primitives::{Uint, U64};
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Shares(U64);
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Tick(Uint<24, 1>);
+
+    storage_newtype! {
+        struct StorageShares(StorageU64) => Shares;
+    }
+
+    storage_newtype! {
+        struct StorageTick(StorageUint<24, 1>) => Tick;
+    }
+
+    #[test]
+    fn test_newtype_layout() {
+        assert_eq!(StorageShares::SLOT_BYTES, StorageU64::SLOT_BYTES);
+        assert_eq!(StorageTick::SLOT_BYTES, 3);
+        testing::assert_round_trips::<StorageShares>([Shares(U64::MAX), Shares(U64::from(7))]);
+        testing::assert_round_trips::<StorageTick>([Tick(Uint::from(0x123456))]);
+    }
+}
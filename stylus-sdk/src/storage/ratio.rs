@@ -0,0 +1,106 @@
+// Copyright 2023-2024, Offchain Labs, Inc.
+// For licensing, see https://github.com/OffchainLabs/stylus-sdk-rs/blob/main/licenses/COPYRIGHT.md
+
+use super::{fixed::mul_div, Erase, SimpleStorageType, StorageGuardMut, StorageType, StorageU128};
+use alloy_primitives::{U128, U256};
+
+/// Accessor for a ratio of two [`u128`]s packed into a single word, such as an exchange rate.
+///
+/// The layout matches a Solidity struct `{ uint128 num; uint128 den; }`: the numerator
+/// occupies the word's lower 16 bytes and the denominator the upper 16, so reading the
+/// whole ratio costs a single `SLOAD`.
+#[derive(Debug)]
+pub struct StorageRatio {
+    num: StorageU128,
+    den: StorageU128,
+}
+
+impl StorageRatio {
+    /// Gets the underlying numerator and denominator in persistent storage.
+    pub fn get(&self) -> (u128, u128) {
+        (self.num.get().to(), self.den.get().to())
+    }
+
+    /// Sets the underlying numerator and denominator in persistent storage.
+    pub fn set(&mut self, (num, den): (u128, u128)) {
+        self.num.set(U128::from(num));
+        self.den.set(U128::from(den));
+    }
+
+    /// Scales `amount` by the ratio, computing `amount * num / den` rounded down.
+    /// The product is computed with a 512-bit intermediate, so it can't overflow.
+    ///
+    /// Returns `None` if the denominator is zero or the result doesn't fit in a [`U256`].
+    pub fn mul_div(&self, amount: U256) -> Option<U256> {
+        let (num, den) = self.get();
+        mul_div(amount, U256::from(num), U256::from(den))
+    }
+}
+
+impl StorageType for StorageRatio {
+    type Wraps<'a> = (u128, u128);
+    type WrapsMut<'a> = StorageGuardMut<'a, Self>;
+
+    unsafe fn new(slot: U256, offset: u8) -> Self {
+        debug_assert!(offset == 0);
+        Self {
+            num: StorageU128::new(slot, 16),
+            den: StorageU128::new(slot, 0),
+        }
+    }
+
+    fn load<'s>(self) -> Self::Wraps<'s> {
+        self.get()
+    }
+
+    fn load_mut<'s>(self) -> Self::WrapsMut<'s> {
+        StorageGuardMut::new(self)
+    }
+}
+
+impl<'a> SimpleStorageType<'a> for StorageRatio {
+    fn set_by_wrapped(&mut self, value: Self::Wraps<'a>) {
+        self.set(value);
+    }
+}
+
+impl Erase for StorageRatio {
+    fn erase(&mut self) {
+        self.num.erase();
+        self.den.erase();
+    }
+}
+
+impl From<StorageRatio> for (u128, u128) {
+    fn from(value: StorageRatio) -> Self {
+        value.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{testing, GlobalStorage, StorageCache};
+    use alloy_primitives::B256;
+
+    #[test]
+    fn test_ratio() {
+        testing::assert_round_trips::<StorageRatio>([(3, 4), (u128::MAX, 1), (0, 0)]);
+
+        let slot = U256::from(2);
+        let mut rate = unsafe { StorageRatio::new(slot, 0) };
+        assert_eq!(rate.mul_div(U256::from(100)), None);
+
+        rate.set((3, 4));
+        let mut expected = B256::ZERO;
+        expected[15] = 4;
+        expected[31] = 3;
+        assert_eq!(StorageCache::get_word(slot), expected);
+        assert_eq!(rate.mul_div(U256::from(10)), Some(U256::from(7)));
+
+        rate.set((u128::MAX, 1));
+        assert_eq!(rate.mul_div(U256::MAX), None);
+        rate.set((u128::MAX, u128::MAX));
+        assert_eq!(rate.mul_div(U256::MAX), Some(U256::MAX));
+    }
+}
@@ -0,0 +1,274 @@
+// Copyright 2023-2024, Offchain Labs, Inc.
+// For licensing, see https://github.com/OffchainLabs/stylus-sdk-rs/blob/main/licenses/COPYRIGHT.md
+
+use super::{
+    Erase, GlobalStorage, SimpleStorageType, Storage, StorageGuard, StorageGuardMut, StorageType,
+};
+use crate::crypto;
+use alloc::vec::Vec;
+use alloy_primitives::U256;
+use core::{cell::OnceCell, marker::PhantomData};
+
+/// Accessor for a storage-backed vector that keeps a few small elements inline in its header.
+///
+/// The layout mirrors Solidity's short-string optimization for `bytes`. While the vector holds
+/// at most [`INLINE_CAPACITY`](Self::INLINE_CAPACITY) elements, they're packed into the header
+/// slot alongside the length, so a tiny vector costs a single word. The header's rightmost byte
+/// then holds `len * 2`, and elements are packed to its left, from right to left.
+///
+/// Pushing beyond the inline capacity moves the elements to the standard [`StorageVec`] layout:
+/// the header holds `len * 2 + 1` and the elements start at `keccak256(slot)`. The vector stays
+/// in that layout until it's emptied, so alternating pushes and pops around the boundary don't
+/// migrate elements back and forth.
+///
+/// Note: only primitives, which fit within a word, may be elements. Elements as wide as a word
+/// have no inline capacity and are always stored out of line, though the header still differs
+/// from that of a [`StorageVec`].
+///
+/// [`StorageVec`]: super::StorageVec
+pub struct StorageSmallVec<S: StorageType> {
+    slot: U256,
+    base: OnceCell<U256>,
+    marker: PhantomData<S>,
+}
+
+impl<S: StorageType> StorageType for StorageSmallVec<S> {
+    type Wraps<'a> = StorageGuard<'a, StorageSmallVec<S>> where Self: 'a;
+    type WrapsMut<'a> = StorageGuardMut<'a, StorageSmallVec<S>> where Self: 'a;
+
+    unsafe fn new(slot: U256, offset: u8) -> Self {
+        debug_assert!(offset == 0);
+        debug_assert!(S::REQUIRED_SLOTS == 0);
+        Self {
+            slot,
+            base: OnceCell::new(),
+            marker: PhantomData,
+        }
+    }
+
+    fn load<'s>(self) -> Self::Wraps<'s> {
+        StorageGuard::new(self)
+    }
+
+    fn load_mut<'s>(self) -> Self::WrapsMut<'s> {
+        StorageGuardMut::new(self)
+    }
+
+    fn slot_count(&self) -> usize {
+        match self.header() {
+            (_, true) => 1,
+            (len, false) => {
+                let density = 32 / S::SLOT_BYTES;
+                ((len + density - 1) / density).saturating_add(1)
+            }
+        }
+    }
+}
+
+impl<S: StorageType> StorageSmallVec<S> {
+    /// The number of elements that fit in the header alongside the length.
+    pub const INLINE_CAPACITY: usize = 31 / S::SLOT_BYTES;
+
+    /// Returns `true` if the collection contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets the number of elements stored.
+    ///
+    /// # Panics
+    ///
+    /// If the stored length doesn't fit in a [`usize`], which is only possible for corrupt storage.
+    pub fn len(&self) -> usize {
+        self.header().0
+    }
+
+    /// Returns `true` if the elements are stored inline in the header slot.
+    pub fn is_inline(&self) -> bool {
+        self.header().1
+    }
+
+    /// Reads the length and whether the elements are inline.
+    fn header(&self) -> (usize, bool) {
+        let word = Storage::get_word(self.slot);
+        if word[31] & 1 == 0 {
+            return ((word[31] >> 1).into(), true);
+        }
+        let len: U256 = U256::from_be_bytes(word.0) >> 1;
+        let len = len
+            .try_into()
+            .expect("StorageSmallVec length exceeds usize");
+        (len, false)
+    }
+
+    /// Overwrites the length, leaving any inline elements in place.
+    unsafe fn set_header(&mut self, len: usize, inline: bool) {
+        match inline {
+            true => Storage::set_byte(self.slot, 31, (len * 2) as u8),
+            false => {
+                let header = U256::from(len) * U256::from(2) + U256::from(1);
+                Storage::set_word(self.slot, header.into());
+            }
+        }
+    }
+
+    /// Gets an accessor to the element at a given index, if it exists.
+    ///
+    /// Note: the accessor is protected by a [`StorageGuard`], which restricts
+    /// its lifetime to that of `&self`.
+    pub fn getter(&self, index: usize) -> Option<StorageGuard<'_, S>> {
+        let (len, inline) = self.header();
+        if index >= len {
+            return None;
+        }
+        let store = unsafe { self.accessor_unchecked(index, inline) };
+        Some(StorageGuard::new(store))
+    }
+
+    /// Gets the underlying accessor to the element at a given index, even if out of bounds.
+    ///
+    /// # Safety
+    ///
+    /// Enables aliasing. UB if out of bounds.
+    unsafe fn accessor_unchecked(&self, index: usize, inline: bool) -> S {
+        let (slot, offset) = self.index_slot(index, inline);
+        S::new(slot, offset)
+    }
+
+    /// Determines the slot and offset for the element at an index.
+    fn index_slot(&self, index: usize, inline: bool) -> (U256, u8) {
+        let width = S::SLOT_BYTES;
+        if inline {
+            return (self.slot, (31 - width * (index + 1)) as u8);
+        }
+        let density = 32 / width;
+        let slot = self.base().wrapping_add(U256::from(index / density));
+        let offset = 32 - (width * (1 + index % density)) as u8;
+        (slot, offset)
+    }
+
+    /// Determines where in storage the out-of-line elements start.
+    fn base(&self) -> &U256 {
+        self.base
+            .get_or_init(|| crypto::keccak(self.slot.to_be_bytes::<32>()).into())
+    }
+}
+
+impl<'a, S: SimpleStorageType<'a>> StorageSmallVec<S> {
+    /// Gets the element at the given index, if it exists.
+    pub fn get(&self, index: usize) -> Option<S::Wraps<'a>> {
+        let (len, inline) = self.header();
+        if index >= len {
+            return None;
+        }
+        Some(unsafe { self.accessor_unchecked(index, inline) }.into())
+    }
+
+    /// Adds an element to the end of the vector, moving the elements out of the header
+    /// if they no longer fit inline.
+    ///
+    /// # Panics
+    ///
+    /// If the vector's length would overflow.
+    pub fn push(&mut self, value: S::Wraps<'a>) {
+        let (index, mut inline) = self.header();
+        let len = index
+            .checked_add(1)
+            .expect("StorageSmallVec length overflow");
+
+        if inline && len > Self::INLINE_CAPACITY {
+            let values: Vec<S::Wraps<'a>> = (0..index)
+                .map(|i| unsafe { self.accessor_unchecked(i, true) }.into())
+                .collect();
+            inline = false;
+            for (i, value) in values.into_iter().enumerate() {
+                let mut store = unsafe { self.accessor_unchecked(i, false) };
+                store.set_by_wrapped(value);
+            }
+        }
+        unsafe { self.set_header(len, inline) };
+
+        let mut store = unsafe { self.accessor_unchecked(index, inline) };
+        store.set_by_wrapped(value);
+    }
+
+    /// Removes and returns the last element of the vector, if it exists.
+    ///
+    /// Note: the vacated bytes are zeroed. Once empty, the vector returns to the inline layout.
+    pub fn pop(&mut self) -> Option<S::Wraps<'a>> {
+        let (len, inline) = self.header();
+        let index = len.checked_sub(1)?;
+        let value = unsafe { self.accessor_unchecked(index, inline) }.into();
+
+        let (slot, offset) = self.index_slot(index, inline);
+        let zeros = [0; 32];
+        unsafe {
+            Storage::set_fields(slot, &[(offset.into(), &zeros[..S::SLOT_BYTES])]);
+            match index {
+                0 => Storage::clear_word(self.slot),
+                _ => self.set_header(index, inline),
+            }
+        }
+        Some(value)
+    }
+}
+
+impl<S: Erase> Erase for StorageSmallVec<S> {
+    fn erase(&mut self) {
+        let (len, inline) = self.header();
+        if !inline {
+            for i in 0..len {
+                let mut store = unsafe { self.accessor_unchecked(i, false) };
+                store.erase();
+            }
+        }
+        unsafe { Storage::clear_word(self.slot) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::StorageU64;
+    use alloy_primitives::{B256, U64};
+
+    #[test]
+    fn test_small_vec() {
+        let mut vec: StorageSmallVec<StorageU64> = unsafe { StorageSmallVec::new(U256::ZERO, 0) };
+        assert_eq!(StorageSmallVec::<StorageU64>::INLINE_CAPACITY, 3);
+        for i in 1..=3 {
+            vec.push(U64::from(i));
+        }
+        assert!(vec.is_inline());
+        let header = Storage::get_word(U256::ZERO);
+        assert_eq!((header[31], header[30], header[14]), (6, 1, 3));
+
+        vec.push(U64::from(4));
+        assert!(!vec.is_inline());
+        assert_eq!(vec.len(), 4);
+        let header = Storage::get_word(U256::ZERO);
+        assert_eq!(U256::from_be_bytes(header.0), U256::from(9));
+        let first = Storage::get_word(*vec.base());
+        assert_eq!((first[31], first[7]), (1, 4));
+        assert_eq!(vec.get(1), Some(U64::from(2)));
+        assert_eq!(vec.get(4), None);
+
+        assert_eq!(vec.pop(), Some(U64::from(4)));
+        assert!(!vec.is_inline());
+        vec.erase();
+        assert!(vec.is_empty() && vec.is_inline());
+        assert_eq!(Storage::get_word(*vec.base()), B256::ZERO);
+    }
+
+    #[test]
+    fn test_small_vec_pop_to_empty() {
+        let mut vec: StorageSmallVec<StorageU64> = unsafe { StorageSmallVec::new(U256::ZERO, 0) };
+        for i in 1..=4 {
+            vec.push(U64::from(i));
+        }
+        while vec.pop().is_some() {}
+        assert!(vec.is_inline());
+        assert_eq!(Storage::get_word(U256::ZERO), B256::ZERO);
+        assert_eq!(Storage::get_word(*vec.base()), B256::ZERO);
+    }
+}
@@ -0,0 +1,206 @@
+// Copyright 2023-2024, Offchain Labs, Inc.
+// For licensing, see https://github.com/OffchainLabs/stylus-sdk-rs/blob/main/licenses/COPYRIGHT.md
+
+use super::{
+    Erase, SimpleStorageType, StorageGuard, StorageGuardMut, StorageKey, StorageType, StorageVec,
+};
+use alloc::vec::Vec;
+use alloy_primitives::U256;
+use core::marker::PhantomData;
+
+/// Accessor for a storage-backed map whose keys are kept in sorted order, enabling range queries
+/// such as "all orders priced at most `x`".
+///
+/// The keys are stored in ascending order in a [`StorageVec`] rooted at the first slot, where
+/// `S` is the accessor for a key, such as [`StorageU256`](super::StorageU256). The values are
+/// laid out exactly like a [`StorageMap`](super::StorageMap) rooted at the slot after it.
+///
+/// Lookups binary search the keys, reading a logarithmic number of them. Inserting or removing
+/// a key shifts every key after it, so writes cost time linear in the number of larger keys.
+pub struct StorageSortedMap<S: StorageType, V: StorageType> {
+    slot: U256,
+    keys: StorageVec<S>,
+    marker: PhantomData<V>,
+}
+
+impl<S: StorageType, V: StorageType> StorageType for StorageSortedMap<S, V> {
+    type Wraps<'a> = StorageGuard<'a, StorageSortedMap<S, V>> where Self: 'a;
+    type WrapsMut<'a> = StorageGuardMut<'a, StorageSortedMap<S, V>> where Self: 'a;
+
+    const REQUIRED_SLOTS: usize = 2;
+
+    unsafe fn new(slot: U256, offset: u8) -> Self {
+        debug_assert!(offset == 0);
+        Self {
+            slot,
+            keys: StorageVec::new(slot, 0),
+            marker: PhantomData,
+        }
+    }
+
+    fn load<'s>(self) -> Self::Wraps<'s> {
+        StorageGuard::new(self)
+    }
+
+    fn load_mut<'s>(self) -> Self::WrapsMut<'s> {
+        StorageGuardMut::new(self)
+    }
+}
+
+impl<S: StorageType, V: StorageType> StorageSortedMap<S, V> {
+    /// Returns the number of keys in the map.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns `true` if the map holds no keys.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Gets the underlying accessor to the value at a given key, even if the key is absent.
+    ///
+    /// # Safety
+    ///
+    /// Enables aliasing.
+    unsafe fn value(&self, key: &impl StorageKey) -> V {
+        let root = self.slot + U256::from(1);
+        let slot = key.to_slot(root.into());
+        V::new(slot, 32 - V::SLOT_BYTES as u8)
+    }
+}
+
+impl<'a, S, V> StorageSortedMap<S, V>
+where
+    S: SimpleStorageType<'a>,
+    S::Wraps<'a>: StorageKey + Ord,
+    V: SimpleStorageType<'a>,
+{
+    /// Finds the index of a key, or the index at which it would be inserted.
+    fn search(&self, key: &S::Wraps<'a>) -> Result<usize, usize> {
+        let index = self.keys.partition_point(|k| k < key);
+        match self.keys.get_range(index, index + 1).first() {
+            Some(found) if found == key => Ok(index),
+            _ => Err(index),
+        }
+    }
+
+    /// Returns `true` if the map holds the given key.
+    pub fn contains_key(&self, key: &S::Wraps<'a>) -> bool {
+        self.search(key).is_ok()
+    }
+
+    /// Gets the value at the given key, if the key is present.
+    pub fn get(&self, key: &S::Wraps<'a>) -> Option<V::Wraps<'a>> {
+        self.search(key).ok()?;
+        Some(unsafe { self.value(key) }.into())
+    }
+
+    /// Sets the value at a given key, inserting the key in order if it's absent.
+    /// Returns the prior value, if the key was present.
+    ///
+    /// # Panics
+    ///
+    /// If the number of keys would overflow.
+    pub fn insert(&mut self, key: S::Wraps<'a>, value: V::Wraps<'a>) -> Option<V::Wraps<'a>> {
+        // intentionally alias so that we can write back after load
+        let mut store = unsafe { self.value(&key) };
+        let prior = match self.search(&key) {
+            Ok(_) => Some(unsafe { self.value(&key) }.into()),
+            Err(index) => {
+                let tail = self.keys.get_range(index, self.keys.len());
+                self.keys.truncate(index);
+                self.keys.push(key);
+                self.keys.extend(tail);
+                None
+            }
+        };
+        store.set_by_wrapped(value);
+        prior
+    }
+
+    /// Removes a key and its value, shifting the larger keys down to keep them in order.
+    /// Returns the value, if the key was present.
+    pub fn remove(&mut self, key: &S::Wraps<'a>) -> Option<V::Wraps<'a>> {
+        let index = self.search(key).ok()?;
+        self.keys.remove(index);
+
+        // intentionally alias so that we can erase after load
+        let mut store = unsafe { self.value(key) };
+        let value = unsafe { self.value(key) }.into();
+        store.erase();
+        Some(value)
+    }
+
+    /// Reads the entries whose keys lie in `lo..hi`, in ascending order of key.
+    /// Binary search locates the bounds, so only the entries in range are read in full.
+    pub fn range(&self, lo: &S::Wraps<'a>, hi: &S::Wraps<'a>) -> Vec<(S::Wraps<'a>, V::Wraps<'a>)> {
+        let start = self.keys.partition_point(|k| k < lo);
+        let end = self.keys.partition_point(|k| k < hi).max(start);
+        let keys = self.keys.get_range(start, end);
+        keys.into_iter()
+            .map(|key| {
+                let value = unsafe { self.value(&key) }.into();
+                (key, value)
+            })
+            .collect()
+    }
+}
+
+impl<'a, S, V> Erase for StorageSortedMap<S, V>
+where
+    S: SimpleStorageType<'a>,
+    S::Wraps<'a>: StorageKey,
+    V: SimpleStorageType<'a>,
+{
+    fn erase(&mut self) {
+        for key in self.keys.take() {
+            let mut store = unsafe { self.value(&key) };
+            store.erase();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{GlobalStorage, Storage, StorageMap, StorageU256, StorageU64};
+    use alloy_primitives::{B256, U64};
+
+    fn u64s(entries: Vec<(U64, U256)>) -> Vec<(u64, u64)> {
+        entries.into_iter().map(|(k, v)| (k.to(), v.to())).collect()
+    }
+
+    #[test]
+    fn test_sorted_map() {
+        let mut map: StorageSortedMap<StorageU64, StorageU256> =
+            unsafe { StorageSortedMap::new(U256::ZERO, 0) };
+        for key in [30, 10, 50, 20, 40] {
+            assert_eq!(map.insert(U64::from(key), U256::from(key + 1)), None);
+        }
+        let prior = map.insert(U64::from(20), U256::from(2));
+        assert_eq!(prior, Some(U256::from(21)));
+        assert_eq!(map.len(), 5);
+        assert_eq!(map.get(&U64::from(20)), Some(U256::from(2)));
+        assert_eq!(map.get(&U64::from(25)), None);
+
+        let range = map.range(&U64::from(15), &U64::from(40));
+        assert_eq!(u64s(range), [(20, 2), (30, 31)]);
+        assert_eq!(map.range(&U64::from(40), &U64::from(15)).len(), 0);
+
+        assert_eq!(map.remove(&U64::from(10)), Some(U256::from(11)));
+        assert_eq!(map.remove(&U64::from(10)), None);
+        assert!(!map.contains_key(&U64::from(10)));
+        let all = map.range(&U64::ZERO, &U64::MAX);
+        assert_eq!(u64s(all), [(20, 2), (30, 31), (40, 41), (50, 51)]);
+
+        // values are laid out as a map rooted at the next slot
+        let values: StorageMap<U64, StorageU256> = unsafe { StorageMap::new(U256::from(1), 0) };
+        assert_eq!(values.get(U64::from(50)), U256::from(51));
+
+        map.erase();
+        assert!(map.is_empty());
+        assert_eq!(values.get(U64::from(50)), U256::ZERO);
+        assert_eq!(Storage::get_word(U256::ZERO), B256::ZERO);
+    }
+}
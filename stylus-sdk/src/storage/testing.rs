@@ -0,0 +1,238 @@
+// Copyright 2023-2024, Offchain Labs, Inc.
+// For licensing, see https://github.com/OffchainLabs/stylus-sdk-rs/blob/main/licenses/COPYRIGHT.md
+
+//! An in-memory stand-in for the Stylus VM's storage, for testing storage types off-chain.
+//!
+//! This module is only available when the `storage-test` feature flag is enabled, and never on
+//! `wasm32`, where contracts must use the VM's own hostios. It provides native implementations
+//! of the storage hostios so that [`StorageCache`](super::StorageCache) and every accessor built
+//! on it can run in ordinary `cargo test` binaries.
+//! State is kept per-thread, so tests running in parallel don't observe each other's writes.
+//!
+//! ```ignore
+//! use stylus_sdk::storage::{testing, StorageU64};
+//! use stylus_sdk::alloy_primitives::U64;
+//!
+//! testing::assert_round_trips::<StorageU64>([U64::ZERO, U64::from(1), U64::MAX]);
+//! ```
+
+use super::{GlobalStorage, SimpleStorageType, StorageCache};
+use alloy_primitives::{B256, U256};
+use core::fmt::Debug;
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    ops::Range,
+};
+
+/// The bytes a struct field occupies within a word.
+struct Claim {
+    field: &'static str,
+    range: Range<usize>,
+    /// Whether the field is a composite, whose words hold nested fields.
+    composite: bool,
+    /// Whether the claim was made while constructing a composite's contents.
+    nested: bool,
+}
+
+std::thread_local! {
+    /// Words persisted to the simulated EVM state trie.
+    static PERSISTED: RefCell<HashMap<B256, B256>> = RefCell::new(HashMap::new());
+
+    /// Words written to the simulated VM cache but not yet flushed.
+    static CACHED: RefCell<HashMap<B256, B256>> = RefCell::new(HashMap::new());
+
+    /// Words written to simulated transient storage.
+    static TRANSIENT: RefCell<HashMap<B256, B256>> = RefCell::new(HashMap::new());
+
+    /// Byte ranges claimed by struct fields, keyed by slot, for detecting layout collisions.
+    static CLAIMS: RefCell<HashMap<U256, Vec<Claim>>> = RefCell::new(HashMap::new());
+
+    /// How many composites are currently being constructed.
+    static NESTING: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Erases all simulated storage, whether persisted, cached, or transient.
+pub fn reset() {
+    PERSISTED.with(|words| words.borrow_mut().clear());
+    CACHED.with(|words| words.borrow_mut().clear());
+    TRANSIENT.with(|words| words.borrow_mut().clear());
+    CLAIMS.with(|claims| claims.borrow_mut().clear());
+    NESTING.with(|depth| depth.set(0));
+}
+
+/// Reads the word persisted at the given key, ignoring any unflushed writes.
+pub fn persisted(key: U256) -> B256 {
+    let key = B256::from(key);
+    PERSISTED.with(|words| words.borrow().get(&key).copied().unwrap_or_default())
+}
+
+/// Seeds the simulated state trie with the given words, as if persisted by an earlier call.
+///
+/// Unlike [`StorageCache::set_word`], preloaded words aren't pending writes, so a subsequent
+/// flush won't store them again. This is useful for establishing a starting state before
+/// exercising logic that reads it.
+pub fn preload(entries: &[(U256, B256)]) {
+    PERSISTED.with(|words| {
+        let mut words = words.borrow_mut();
+        for (key, value) in entries {
+            words.insert(B256::from(*key), *value);
+        }
+    });
+}
+
+/// Discards simulated transient storage, as happens at the end of a transaction.
+pub fn end_transaction() {
+    TRANSIENT.with(|words| words.borrow_mut().clear());
+}
+
+/// Returns the number of words written to the simulated VM cache but not yet flushed.
+pub fn pending_writes() -> usize {
+    CACHED.with(|words| words.borrow().len())
+}
+
+/// Records that a struct field occupies `bytes` bytes of slot `slot`, starting at `offset`.
+///
+/// # Panics
+///
+/// If a differently-named field has already claimed any of the same bytes.
+pub(crate) fn claim(field: &'static str, slot: U256, offset: u8, bytes: usize) {
+    let range = offset as usize..offset as usize + bytes;
+    record(field, slot, range, false);
+}
+
+/// Records that a composite struct field spans `words` whole words, starting at slot `slot`.
+/// Unlike other fields, those constructed within [`nest`] may overlap these words.
+///
+/// # Panics
+///
+/// If a differently-named field has already claimed any of the same bytes.
+pub(crate) fn claim_words(field: &'static str, slot: U256, words: usize) {
+    for word in 0..words {
+        record(field, slot + U256::from(word), 0..32, true);
+    }
+}
+
+/// Constructs the contents of a composite, whose fields may lie within its claimed words.
+pub(crate) fn nest<T>(new: impl FnOnce() -> T) -> T {
+    NESTING.with(|depth| depth.set(depth.get() + 1));
+    let value = new();
+    NESTING.with(|depth| depth.set(depth.get() - 1));
+    value
+}
+
+fn record(field: &'static str, slot: U256, range: Range<usize>, composite: bool) {
+    let nested = NESTING.with(|depth| depth.get() > 0);
+    CLAIMS.with(|claims| {
+        let mut claims = claims.borrow_mut();
+        let regions = claims.entry(slot).or_default();
+        for other in regions.iter() {
+            let overlaps = other.range.start < range.end && range.start < other.range.end;
+            let encloses = (other.composite && nested) || (composite && other.nested);
+            assert!(
+                other.field == field || !overlaps || encloses,
+                "storage collision: `{field}` overlaps `{}` in slot {slot:#x}",
+                other.field
+            );
+        }
+        if !regions.iter().any(|c| c.field == field && c.range == range) {
+            regions.push(Claim {
+                field,
+                range,
+                composite,
+                nested,
+            });
+        }
+    });
+}
+
+/// Reads the 32-byte word at `key`, preferring unflushed writes.
+fn load(key: B256) -> B256 {
+    if let Some(value) = CACHED.with(|words| words.borrow().get(&key).copied()) {
+        return value;
+    }
+    PERSISTED.with(|words| words.borrow().get(&key).copied().unwrap_or_default())
+}
+
+/// Simulates the VM's `storage_load_bytes32` hostio.
+#[no_mangle]
+unsafe extern "C" fn storage_load_bytes32(key: *const u8, dest: *mut u8) {
+    let key = B256::from_slice(core::slice::from_raw_parts(key, 32));
+    let value = load(key);
+    core::ptr::copy(value.as_ptr(), dest, 32);
+}
+
+/// Simulates the VM's `storage_cache_bytes32` hostio.
+#[no_mangle]
+unsafe extern "C" fn storage_cache_bytes32(key: *const u8, value: *const u8) {
+    let key = B256::from_slice(core::slice::from_raw_parts(key, 32));
+    let value = B256::from_slice(core::slice::from_raw_parts(value, 32));
+    CACHED.with(|words| words.borrow_mut().insert(key, value));
+}
+
+/// Simulates the VM's `transient_load_bytes32` hostio.
+#[no_mangle]
+unsafe extern "C" fn transient_load_bytes32(key: *const u8, dest: *mut u8) {
+    let key = B256::from_slice(core::slice::from_raw_parts(key, 32));
+    let value = TRANSIENT.with(|words| words.borrow().get(&key).copied().unwrap_or_default());
+    core::ptr::copy(value.as_ptr(), dest, 32);
+}
+
+/// Simulates the VM's `transient_store_bytes32` hostio.
+#[no_mangle]
+unsafe extern "C" fn transient_store_bytes32(key: *const u8, value: *const u8) {
+    let key = B256::from_slice(core::slice::from_raw_parts(key, 32));
+    let value = B256::from_slice(core::slice::from_raw_parts(value, 32));
+    TRANSIENT.with(|words| words.borrow_mut().insert(key, value));
+}
+
+/// Simulates the VM's `storage_flush_cache` hostio.
+/// Since this backend never caches reads, `clear` has no additional effect.
+#[no_mangle]
+unsafe extern "C" fn storage_flush_cache(_clear: bool) {
+    let dirty = CACHED.with(|words| core::mem::take(&mut *words.borrow_mut()));
+    PERSISTED.with(|words| words.borrow_mut().extend(dirty));
+}
+
+/// Asserts that each value survives being written through an accessor of type `S` and read back.
+///
+/// The accessor is placed at every offset a packed field of its width may occupy within a word,
+/// each time surrounded by sentinel bytes. Besides checking the value itself, the harness
+/// asserts that no byte outside the accessor's [`SLOT_BYTES`](super::StorageType::SLOT_BYTES)
+/// is modified, catching offset and alignment bugs.
+///
+/// Note: this overwrites a single slot at an arbitrary location in the simulated storage.
+pub fn assert_round_trips<S>(values: impl IntoIterator<Item = S::Wraps<'static>>)
+where
+    S: SimpleStorageType<'static>,
+    S::Wraps<'static>: Clone + Debug + PartialEq,
+{
+    const SENTINEL: B256 = B256::repeat_byte(0xa5);
+
+    let slot = U256::from_be_bytes(SENTINEL.0) - U256::from(1);
+    let width = S::SLOT_BYTES;
+
+    for value in values {
+        for offset in 0..=32 - width {
+            unsafe { StorageCache::set_word(slot, SENTINEL) };
+
+            let mut store = unsafe { S::new(slot, offset as u8) };
+            store.set_by_wrapped(value.clone());
+
+            let cached: S::Wraps<'static> = store.into();
+            assert_eq!(cached, value, "cached value differs at offset {offset}");
+
+            let fresh: S::Wraps<'static> = unsafe { S::new(slot, offset as u8) }.into();
+            assert_eq!(fresh, value, "stored value differs at offset {offset}");
+
+            let word = StorageCache::get_word(slot);
+            let outside = (0..32).filter(|i| *i < offset || *i >= offset + width);
+            for i in outside {
+                assert_eq!(
+                    word[i], SENTINEL[i],
+                    "byte {i} clobbered at offset {offset}"
+                );
+            }
+        }
+    }
+}
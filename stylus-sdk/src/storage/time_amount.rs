@@ -0,0 +1,114 @@
+// Copyright 2023-2024, Offchain Labs, Inc.
+// For licensing, see https://github.com/OffchainLabs/stylus-sdk-rs/blob/main/licenses/COPYRIGHT.md
+
+use super::{Erase, SimpleStorageType, StorageGuardMut, StorageType, StorageU192, StorageU64};
+use alloy_primitives::{aliases::U192, U256, U64};
+
+/// Accessor for a [`u64`] timestamp and a [`U192`] amount packed into a single word,
+/// such as the start time and allocation of a vesting schedule.
+///
+/// The layout matches a Solidity struct `{ uint64 start; uint192 amount; }`: the timestamp
+/// occupies the word's lowest 8 bytes and the amount the 24 bytes above them. Each getter and
+/// setter touches only its own bytes, while [`get`](Self::get) costs a single `SLOAD`.
+#[derive(Debug)]
+pub struct StorageTimeAmount {
+    timestamp: StorageU64,
+    amount: StorageU192,
+}
+
+impl StorageTimeAmount {
+    /// Gets the underlying timestamp in persistent storage.
+    pub fn get_timestamp(&self) -> u64 {
+        self.timestamp.get().to()
+    }
+
+    /// Sets the underlying timestamp in persistent storage.
+    pub fn set_timestamp(&mut self, value: u64) {
+        self.timestamp.set(U64::from(value));
+    }
+
+    /// Gets the underlying amount in persistent storage.
+    pub fn get_amount(&self) -> U192 {
+        self.amount.get()
+    }
+
+    /// Sets the underlying amount in persistent storage.
+    pub fn set_amount(&mut self, value: U192) {
+        self.amount.set(value);
+    }
+
+    /// Gets both the timestamp and the amount.
+    pub fn get(&self) -> (u64, U192) {
+        (self.get_timestamp(), self.get_amount())
+    }
+
+    /// Sets both the timestamp and the amount.
+    pub fn set(&mut self, (timestamp, amount): (u64, U192)) {
+        self.set_timestamp(timestamp);
+        self.set_amount(amount);
+    }
+}
+
+impl StorageType for StorageTimeAmount {
+    type Wraps<'a> = (u64, U192);
+    type WrapsMut<'a> = StorageGuardMut<'a, Self>;
+
+    unsafe fn new(slot: U256, offset: u8) -> Self {
+        debug_assert!(offset == 0);
+        Self {
+            timestamp: StorageU64::new(slot, 24),
+            amount: StorageU192::new(slot, 0),
+        }
+    }
+
+    fn load<'s>(self) -> Self::Wraps<'s> {
+        self.get()
+    }
+
+    fn load_mut<'s>(self) -> Self::WrapsMut<'s> {
+        StorageGuardMut::new(self)
+    }
+}
+
+impl<'a> SimpleStorageType<'a> for StorageTimeAmount {
+    fn set_by_wrapped(&mut self, value: Self::Wraps<'a>) {
+        self.set(value);
+    }
+}
+
+impl Erase for StorageTimeAmount {
+    fn erase(&mut self) {
+        self.timestamp.erase();
+        self.amount.erase();
+    }
+}
+
+impl From<StorageTimeAmount> for (u64, U192) {
+    fn from(value: StorageTimeAmount) -> Self {
+        value.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{testing, GlobalStorage, StorageCache};
+    use alloy_primitives::B256;
+
+    #[test]
+    fn test_time_amount_layout() {
+        let start = 1_700_000_000;
+        testing::assert_round_trips::<StorageTimeAmount>([(start, U192::MAX), (0, U192::ZERO)]);
+
+        let slot = U256::from(2);
+        let mut grant = unsafe { StorageTimeAmount::new(slot, 0) };
+        grant.set_amount(U192::from(0x0102));
+        grant.set_timestamp(start);
+
+        let mut expected = B256::ZERO;
+        expected[22..24].copy_from_slice(&[0x01, 0x02]);
+        expected[24..].copy_from_slice(&start.to_be_bytes());
+        assert_eq!(StorageCache::get_word(slot), expected);
+        assert_eq!(grant.get(), (start, U192::from(0x0102)));
+    }
+}
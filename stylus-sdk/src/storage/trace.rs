@@ -0,0 +1,104 @@
+// Copyright 2023-2024, Offchain Labs, Inc.
+// For licensing, see https://github.com/OffchainLabs/stylus-sdk-rs/blob/main/licenses/COPYRIGHT.md
+
+//! Tracing of every storage access, for diagnosing unexpected reads and writes during testing.
+//!
+//! This module is only available when the `trace-storage` feature flag is enabled. Once a tracer
+//! is installed with [`set_tracer`], each word read or written through
+//! [`StorageCache`](super::StorageCache) is reported to it as a [`StorageAccess`], whose
+//! [`Display`](core::fmt::Display) impl renders lines like `SLOAD slot=... value=...`.
+//!
+//! Note: since caching happens in the VM, every access is reported, not just those that miss
+//! the cache. While a tracer is installed, reporting a write's prior value costs an additional
+//! read.
+
+use alloy_primitives::{B256, U256};
+use core::{
+    fmt, mem, ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+/// A single access to persistent storage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageAccess {
+    /// A word was read.
+    Load {
+        /// The slot read from.
+        slot: U256,
+        /// The word read.
+        value: B256,
+    },
+    /// A word was written.
+    Store {
+        /// The slot written to.
+        slot: U256,
+        /// The word previously held in the slot.
+        old: B256,
+        /// The word written.
+        new: B256,
+    },
+}
+
+impl fmt::Display for StorageAccess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Load { slot, value } => write!(f, "SLOAD slot={slot:#x} value={value}"),
+            Self::Store { slot, old, new } => {
+                write!(f, "SSTORE slot={slot:#x} old={old} new={new}")
+            }
+        }
+    }
+}
+
+/// The installed tracer, or null. An atomic keeps [`set_tracer`] sound should tests install
+/// tracers from multiple threads.
+static TRACER: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Installs a callback to be invoked on every storage access, replacing any prior tracer.
+pub fn set_tracer(tracer: fn(StorageAccess)) {
+    TRACER.store(tracer as *mut (), Ordering::Release);
+}
+
+/// Removes the installed tracer, if any.
+pub fn clear_tracer() {
+    TRACER.store(ptr::null_mut(), Ordering::Release);
+}
+
+/// Reports an access to the installed tracer. The access is only built if a tracer is
+/// installed, so untraced writes don't pay for reading the prior value.
+pub(crate) fn record(access: impl FnOnce() -> StorageAccess) {
+    let tracer = TRACER.load(Ordering::Acquire);
+    if tracer.is_null() {
+        return;
+    }
+    // SAFETY: non-null values are only ever stored by `set_tracer`
+    let tracer: fn(StorageAccess) = unsafe { mem::transmute(tracer) };
+    tracer(access());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{GlobalStorage, StorageCache};
+    use std::cell::RefCell;
+
+    std::thread_local! {
+        static LINES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    #[test]
+    fn test_trace() {
+        set_tracer(|access| LINES.with(|lines| lines.borrow_mut().push(access.to_string())));
+        let slot = U256::from(10);
+        let value = B256::repeat_byte(1);
+        unsafe { StorageCache::set_word(slot, value) };
+        StorageCache::get_word(slot);
+        clear_tracer();
+
+        let lines = LINES.with(|lines| lines.take());
+        assert_eq!(lines.len(), 2);
+        let zero = B256::ZERO;
+        assert_eq!(lines[0], format!("SSTORE slot=0xa old={zero} new={value}"));
+        assert_eq!(lines[1], format!("SLOAD slot=0xa value={value}"));
+    }
+}
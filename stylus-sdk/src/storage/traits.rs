@@ -13,6 +13,21 @@ use derivative::Derivative;
 /// Users can implement this trait to add novel data structures to their contract definitions.
 /// The Stylus SDK by default provides only solidity types, which are represented [`the same way`].
 ///
+/// # Composition
+///
+/// Any composite type, whether a collection like [`StorageVec`](super::StorageVec) or
+/// [`StorageMap`](super::StorageMap) or a `#[storage]` struct, may be nested in any other.
+/// This works because each composite type follows the same contract:
+/// - It keeps [`SLOT_BYTES`](Self::SLOT_BYTES) at 32 and is constructed at offset 0, so it
+///   never shares a word with its neighbors.
+/// - It derives the slots of its own contents solely from the slot passed to
+///   [`new`](Self::new): fixed-size data at consecutive slots after it, and dynamic data at
+///   a `keccak256` of it.
+///
+/// Containers then only need to hand each element a distinct slot. For example, the map at
+/// index `i` of a vector at slot `p` lives at `keccak256(p) + i`, and its value for key `k`
+/// at `keccak256(k . (keccak256(p) + i))`, exactly as Solidity would lay out `mapping[]`.
+///
 /// [`the same way`]: https://docs.soliditylang.org/en/latest/internals/layout_in_storage.html
 pub trait StorageType: Sized {
     /// For primitive types, this is the type being stored.
@@ -39,7 +54,8 @@ pub trait StorageType: Sized {
     const SLOT_BYTES: usize = 32;
 
     /// The number of words this type must fill. For primitives this is always 0.
-    /// For complex types requiring more than one inline word, set this to the total size.
+    /// For complex types requiring more than one inline word, set this to the total size,
+    /// and leave [`SLOT_BYTES`](Self::SLOT_BYTES) at 32 so that the type starts on a fresh word.
     const REQUIRED_SLOTS: usize = 0;
 
     /// Where in persistent storage the type should live. Although useful for framework designers
@@ -64,6 +80,19 @@ pub trait StorageType: Sized {
     fn load_mut<'s>(self) -> Self::WrapsMut<'s>
     where
         Self: 's;
+
+    /// The number of words the value currently occupies, which for dynamic collections depends
+    /// on their length. This is useful for generic code that needs to know the extent of a value,
+    /// such as when clearing or hashing it.
+    ///
+    /// By default, this is the type's inline footprint: [`REQUIRED_SLOTS`](Self::REQUIRED_SLOTS),
+    /// or 1 for primitives, which may share their word with neighboring values.
+    /// Collections like [`StorageVec`](super::StorageVec) add the words holding their elements,
+    /// but not any words belonging to the elements themselves. Since a
+    /// [`StorageMap`](super::StorageMap) can't enumerate its keys, it counts only its root slot.
+    fn slot_count(&self) -> usize {
+        Self::REQUIRED_SLOTS.max(1)
+    }
 }
 
 /// Trait for accessors that can be used to completely erase their underlying value.
@@ -100,6 +129,16 @@ pub unsafe trait TopLevelStorage {}
 /// Binds a storage accessor to a lifetime to prevent aliasing.
 /// Because this type doesn't implement `DerefMut`, mutable methods on the accessor aren't available.
 /// For a mutable accessor, see [`StorageGuardMut`].
+///
+/// ```compile_fail,E0596
+/// use stylus_sdk::storage::{StorageBlockNumber, StorageType, StorageVec};
+/// use stylus_sdk::alloy_primitives::U256;
+///
+/// let mut vec: StorageVec<StorageBlockNumber> = unsafe { StorageVec::new(U256::ZERO, 0) };
+/// vec.grow();
+/// let guard = vec.getter(0_usize).unwrap();
+/// guard.set(1); // setters require `&mut self`
+/// ```
 #[derive(Derivative)]
 #[derivative(Debug = "transparent")]
 pub struct StorageGuard<'a, T: 'a> {
@@ -119,9 +158,15 @@ impl<'a, T: 'a> StorageGuard<'a, T> {
 
     /// Get the underlying `T` directly, bypassing the borrow checker.
     ///
+    /// Note: this is deliberately not a safe `into_inner`. Consuming the guard ends its borrow,
+    /// but the returned accessor is no longer bound to `'a`, so it may outlive the collection
+    /// it came from and coexist with a fresh accessor to the same slots.
+    ///
     /// # Safety
     ///
-    /// Enables storage aliasing.
+    /// Enables storage aliasing. The caller must ensure that no other accessor writes to the
+    /// same slots while the returned one is alive.
+    #[doc(alias = "into_inner")]
     pub unsafe fn into_raw(self) -> T {
         self.inner
     }
@@ -152,9 +197,13 @@ impl<'a, T: 'a> StorageGuardMut<'a, T> {
 
     /// Get the underlying `T` directly, bypassing the borrow checker.
     ///
+    /// Note: see [`StorageGuard::into_raw`] for why this isn't a safe `into_inner`.
+    ///
     /// # Safety
     ///
-    /// Enables storage aliasing.
+    /// Enables storage aliasing. The caller must ensure that no other accessor writes to the
+    /// same slots while the returned one is alive.
+    #[doc(alias = "into_inner")]
     pub unsafe fn into_raw(self) -> T {
         self.inner
     }
@@ -199,6 +248,9 @@ pub trait GlobalStorage {
     /// The integer's bytes are read from slot `key`, starting `offset` bytes from the left.
     /// Note that the bytes must exist within a single, 32-byte EVM word.
     ///
+    /// The integer is decoded as big-endian, which is how Solidity packs sub-word values
+    /// and how [`set_uint`](Self::set_uint) encodes them.
+    ///
     /// # Safety
     ///
     /// UB if the read would cross a word boundary.
@@ -257,6 +309,20 @@ pub trait GlobalStorage {
     /// [`generic_const_exprs`]: https://github.com/rust-lang/rust/issues/76560
     fn get_word(key: U256) -> B256;
 
+    /// Retrieves several sub-word values packed into slot `key` with a single read.
+    /// Each field is given as an `(offset, size)` pair in bytes, with offsets counted from the
+    /// left, and is returned as the big-endian unsigned integer its bytes encode.
+    ///
+    /// This is useful for reading a packed struct in one access rather than one per field.
+    ///
+    /// # Panics
+    ///
+    /// If a field extends past the end of the word.
+    fn get_words_packed<const N: usize>(key: U256, fields: [(usize, usize); N]) -> [U256; N] {
+        let word = Self::get_word(key);
+        fields.map(|(offset, size)| U256::from_be_slice(&word[offset..][..size]))
+    }
+
     /// Writes `N ≤ 32` bytes to persistent storage, performing [`SSTORE`]'s only as needed.
     /// The bytes are written to slot `key`, starting `offset` bytes from the left.
     /// Note that the bytes must be written to a single, 32-byte EVM word.
@@ -270,6 +336,9 @@ pub trait GlobalStorage {
     unsafe fn set<const N: usize>(key: U256, offset: usize, value: FixedBytes<N>) {
         debug_assert!(N + offset <= 32);
 
+        if N == 0 {
+            return; // zero-width values occupy no storage
+        }
         if N == 32 {
             return Self::set_word(key, FixedBytes::from_slice(value.as_slice()));
         }
@@ -286,6 +355,8 @@ pub trait GlobalStorage {
     /// The integer's bytes are written to slot `key`, starting `offset` bytes from the left.
     /// Note that the bytes must be written to a single, 32-byte EVM word.
     ///
+    /// The integer is encoded as big-endian, mirroring [`get_uint`](Self::get_uint).
+    ///
     /// # Safety
     ///
     /// UB if the write would cross a word boundary.
@@ -299,6 +370,9 @@ pub trait GlobalStorage {
     ) {
         debug_assert!(B / 8 + offset <= 32);
 
+        if B == 0 {
+            return; // zero-width values occupy no storage
+        }
         if B == 256 {
             return Self::set_word(key, FixedBytes::from_slice(&value.to_be_bytes::<32>()));
         }
@@ -343,6 +417,27 @@ pub trait GlobalStorage {
         Self::set::<1>(key, offset, fixed)
     }
 
+    /// Writes several sub-word values into slot `key` at once, reading and writing the word
+    /// only one time. Each write is given as an `(offset, bytes)` pair, with the offset counted
+    /// from the left, and writes are applied in order.
+    ///
+    /// This is useful for initializing the fields of a packed struct together.
+    ///
+    /// # Safety
+    ///
+    /// Aliases if called during the lifetime an overlapping accessor.
+    ///
+    /// # Panics
+    ///
+    /// If a write extends past the end of the word.
+    unsafe fn set_fields(key: U256, writes: &[(usize, &[u8])]) {
+        let mut word = Self::get_word(key);
+        for (offset, bytes) in writes {
+            word[*offset..][..bytes.len()].copy_from_slice(bytes);
+        }
+        Self::set_word(key, word);
+    }
+
     /// Stores a 32-byte EVM word to persistent storage, performing [`SSTORE`]'s only as needed.
     ///
     /// # Safety
@@ -353,6 +448,8 @@ pub trait GlobalStorage {
     unsafe fn set_word(key: U256, value: B256);
 
     /// Clears the 32-byte word at the given key, performing [`SSTORE`]'s only as needed.
+    /// This is the single-slot building block for erasing values, and any storage refund
+    /// is realized when the cleared word is flushed.
     ///
     /// # Safety
     ///
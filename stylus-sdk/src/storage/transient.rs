@@ -0,0 +1,170 @@
+// Copyright 2023-2024, Offchain Labs, Inc.
+// For licensing, see https://github.com/OffchainLabs/stylus-sdk-rs/blob/main/licenses/COPYRIGHT.md
+
+use super::GlobalStorage;
+use crate::hostio;
+use alloy_primitives::{B256, U256};
+
+/// Global accessor to transient storage, as introduced by [EIP-1153].
+///
+/// Transient words are read and written like persistent ones, but are discarded at the end of
+/// the transaction and never reach the EVM state trie. This makes them a cheap home for values
+/// that only matter within a transaction, such as reentrancy locks.
+///
+/// Note: accessors like [`StorageBool`](super::StorageBool) are built on
+/// [`StorageCache`](super::StorageCache), so transient slots are accessed through the methods
+/// of [`GlobalStorage`] instead, or through [`TransientBool`] and [`TransientU256`].
+/// Transient slots are independent of persistent ones, so slot `0` may be used here without
+/// colliding with a contract's first storage field.
+///
+/// ```no_run
+/// use stylus_sdk::storage::{GlobalStorage, TransientStorage};
+/// use stylus_sdk::alloy_primitives::U256;
+///
+/// let lock = U256::ZERO;
+/// unsafe {
+///     assert_eq!(TransientStorage::get_byte(lock, 31), 0, "reentrant call");
+///     TransientStorage::set_byte(lock, 31, 1);
+/// }
+/// // ... make external calls ...
+/// unsafe { TransientStorage::clear_word(lock) };
+/// ```
+///
+/// [EIP-1153]: https://eips.ethereum.org/EIPS/eip-1153
+pub struct TransientStorage;
+
+impl GlobalStorage for TransientStorage {
+    /// Retrieves a 32-byte EVM word from transient storage.
+    fn get_word(key: U256) -> B256 {
+        let mut data = B256::ZERO;
+        unsafe { hostio::transient_load_bytes32(B256::from(key).as_ptr(), data.as_mut_ptr()) };
+        data
+    }
+
+    /// Stores a 32-byte EVM word to transient storage.
+    ///
+    /// # Safety
+    ///
+    /// May alias storage.
+    unsafe fn set_word(key: U256, value: B256) {
+        hostio::transient_store_bytes32(B256::from(key).as_ptr(), value.as_ptr())
+    }
+}
+
+/// Accessor for a [`bool`] in transient storage.
+///
+/// Unlike [`StorageBool`](super::StorageBool), values are not cached, since a reentrant call
+/// may change them and transient reads are cheap.
+///
+/// ```no_run
+/// use stylus_sdk::storage::TransientBool;
+/// use stylus_sdk::alloy_primitives::U256;
+///
+/// let mut lock = unsafe { TransientBool::new(U256::ZERO, 0) };
+/// assert!(!lock.get(), "reentrant call");
+/// lock.set(true);
+/// // ... make external calls ...
+/// lock.set(false);
+/// ```
+#[derive(Debug)]
+pub struct TransientBool {
+    slot: U256,
+    offset: u8,
+}
+
+impl TransientBool {
+    /// Creates an accessor for the byte at `offset` in transient slot `slot`.
+    ///
+    /// # Safety
+    ///
+    /// Aliases the transient slot, which other accessors may also write to.
+    pub unsafe fn new(slot: U256, offset: u8) -> Self {
+        debug_assert!(offset < 32);
+        Self { slot, offset }
+    }
+
+    /// Gets the underlying [`bool`] in transient storage.
+    pub fn get(&self) -> bool {
+        unsafe { TransientStorage::get_byte(self.slot, self.offset.into()) != 0 }
+    }
+
+    /// Sets the underlying [`bool`] in transient storage.
+    pub fn set(&mut self, value: bool) {
+        unsafe { TransientStorage::set_byte(self.slot, self.offset.into(), value as u8) }
+    }
+}
+
+/// Accessor for a [`U256`] in transient storage, which occupies an entire slot.
+///
+/// Like [`TransientBool`], values are read from transient storage on each access.
+#[derive(Debug)]
+pub struct TransientU256 {
+    slot: U256,
+}
+
+impl TransientU256 {
+    /// Creates an accessor for transient slot `slot`.
+    ///
+    /// # Safety
+    ///
+    /// Aliases the transient slot, which other accessors may also write to.
+    pub unsafe fn new(slot: U256) -> Self {
+        Self { slot }
+    }
+
+    /// Gets the underlying [`U256`] in transient storage.
+    pub fn get(&self) -> U256 {
+        TransientStorage::get_word(self.slot).into()
+    }
+
+    /// Sets the underlying [`U256`] in transient storage.
+    pub fn set(&mut self, value: U256) {
+        unsafe { TransientStorage::set_word(self.slot, value.into()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{testing, StorageCache};
+
+    #[test]
+    fn test_transient() {
+        let slot = U256::from(1);
+        unsafe { TransientStorage::set_byte(slot, 31, 1) };
+        assert_eq!(TransientStorage::get_word(slot)[31], 1);
+        assert_eq!(StorageCache::get_word(slot), B256::ZERO);
+        assert_eq!(testing::pending_writes(), 0);
+
+        StorageCache::flush();
+        assert_eq!(testing::persisted(slot), B256::ZERO);
+        testing::end_transaction();
+        assert_eq!(TransientStorage::get_word(slot), B256::ZERO);
+    }
+
+    #[test]
+    fn test_transient_bool() {
+        let slot = U256::from(2);
+        let mut flag = unsafe { TransientBool::new(slot, 31) };
+        let neighbor = unsafe { TransientBool::new(slot, 0) };
+        flag.set(true);
+        assert!(flag.get());
+        assert!(!neighbor.get());
+        assert_eq!(StorageCache::get_word(slot), B256::ZERO);
+
+        testing::end_transaction();
+        assert!(!flag.get());
+    }
+
+    #[test]
+    fn test_transient_u256() {
+        let slot = U256::from(3);
+        let mut value = unsafe { TransientU256::new(slot) };
+        value.set(U256::MAX);
+        assert_eq!(value.get(), U256::MAX);
+        assert_eq!(StorageCache::get_word(slot), B256::ZERO);
+
+        testing::end_transaction();
+        assert_eq!(value.get(), U256::ZERO);
+    }
+}
@@ -2,16 +2,23 @@
 // For licensing, see https://github.com/OffchainLabs/stylus-sdk-rs/blob/main/licenses/COPYRIGHT.md
 
 use super::{
-    Erase, GlobalStorage, SimpleStorageType, Storage, StorageGuard, StorageGuardMut, StorageType,
+    Erase, GlobalStorage, SimpleStorageType, Storage, StorageError, StorageGuard, StorageGuardMut,
+    StorageType, StorageUint,
 };
 use crate::crypto;
-use alloy_primitives::U256;
-use core::{cell::OnceCell, marker::PhantomData};
+use alloc::vec::Vec;
+use alloy_primitives::{ruint::UintTryFrom, Uint, B256, U256};
+use core::{
+    cell::{Cell, OnceCell},
+    marker::PhantomData,
+};
 
 /// Accessor for a storage-backed vector.
 pub struct StorageVec<S: StorageType> {
     slot: U256,
     base: OnceCell<U256>,
+    /// The length, along with the [`StorageCache`](super::StorageCache) epoch in which it was read.
+    len: Cell<Option<(usize, usize)>>,
     marker: PhantomData<S>,
 }
 
@@ -24,6 +31,7 @@ impl<S: StorageType> StorageType for StorageVec<S> {
         Self {
             slot,
             base: OnceCell::new(),
+            len: Cell::new(None),
             marker: PhantomData,
         }
     }
@@ -35,6 +43,10 @@ impl<S: StorageType> StorageType for StorageVec<S> {
     fn load_mut<'s>(self) -> Self::WrapsMut<'s> {
         StorageGuardMut::new(self)
     }
+
+    fn slot_count(&self) -> usize {
+        self.data_words().saturating_add(1)
+    }
 }
 
 impl<S: StorageType> StorageVec<S> {
@@ -43,10 +55,38 @@ impl<S: StorageType> StorageVec<S> {
         self.len() == 0
     }
 
+    /// The largest length for which every element's slot can be computed.
+    const MAX_LEN: usize = match S::REQUIRED_SLOTS {
+        0 => usize::MAX,
+        words => usize::MAX / words,
+    };
+
     /// Gets the number of elements stored.
+    ///
+    /// The length is cached by the accessor until storage is next written, flushed, or cleared,
+    /// so checking it repeatedly between writes reads it only once. Because any write invalidates
+    /// the cache, the length stays in sync with writes made through other accessors, and with
+    /// those made by reentrant calls, after which the cache is cleared.
+    ///
+    /// # Panics
+    ///
+    /// If the stored length doesn't fit in a [`usize`], which is only possible for corrupt storage.
     pub fn len(&self) -> usize {
+        self.try_len().expect("StorageVec length exceeds usize")
+    }
+
+    /// Gets the number of elements stored, erroring if the stored length doesn't fit a [`usize`].
+    fn try_len(&self) -> Result<usize, StorageError> {
+        let epoch = Storage::epoch();
+        if let Some((len, cached)) = self.len.get() {
+            if cached == epoch {
+                return Ok(len);
+            }
+        }
         let word: U256 = Storage::get_word(self.slot).into();
-        word.try_into().unwrap()
+        let len = word.try_into().map_err(|_| StorageError::LengthOverflow)?;
+        self.len.set(Some((len, epoch)));
+        Ok(len)
     }
 
     /// Overwrites the vector's length.
@@ -55,9 +95,47 @@ impl<S: StorageType> StorageVec<S> {
     ///
     /// It must be sensible to create accessors for `S` from zero-slots,
     /// or any junk data left over from prior dirty operations.
-    /// Note that [`StorageVec`] has unlimited capacity, so all lengths are valid.
+    /// Note that [`StorageVec`] has unlimited capacity, so all addressable lengths are valid.
+    ///
+    /// # Panics
+    ///
+    /// If `len` is so large that the slots of its elements can't be addressed.
     pub unsafe fn set_len(&mut self, len: usize) {
-        Storage::set_word(self.slot, U256::from(len).into())
+        assert!(len <= Self::MAX_LEN, "StorageVec length exceeds maximum");
+        Storage::set_word(self.slot, U256::from(len).into());
+        self.len.set(Some((len, Storage::epoch())));
+    }
+
+    /// The slot holding the vector's length, as in Solidity's layout for dynamic arrays.
+    pub fn header_slot(&self) -> U256 {
+        self.slot
+    }
+
+    /// The slot at which the vector's elements begin, `keccak256(header_slot)`.
+    pub fn data_base(&self) -> U256 {
+        *self.base()
+    }
+
+    /// Reads the raw words of the vector's data region, starting at [`data_base`](Self::data_base).
+    /// Packed elements share words, so this returns only as many words as the elements span.
+    /// This is useful for custom serialization or commitment schemes.
+    pub fn raw_words(&self) -> Vec<B256> {
+        let base = *self.base();
+        (0..self.data_words())
+            .map(|i| Storage::get_word(base + U256::from(i)))
+            .collect()
+    }
+
+    /// The number of words spanned by the elements.
+    fn data_words(&self) -> usize {
+        let len = self.len();
+        match S::REQUIRED_SLOTS {
+            0 => {
+                let density = self.density();
+                (len + density - 1) / density
+            }
+            words => len.saturating_mul(words),
+        }
     }
 
     /// Gets an accessor to the element at a given index, if it exists.
@@ -69,11 +147,30 @@ impl<S: StorageType> StorageVec<S> {
         Some(StorageGuard::new(store))
     }
 
+    /// Gets an accessor to the element at a [`U256`] index, such as one decoded from calldata.
+    ///
+    /// Unlike [`getter`](Self::getter), this distinguishes an index too large to fit in a
+    /// [`usize`], which is an error, from one that's merely out of bounds, which is `Ok(None)`.
+    pub fn get_checked(&self, index: U256) -> Result<Option<StorageGuard<'_, S>>, StorageError> {
+        let index: usize = index.try_into().map_err(|_| StorageError::IndexOverflow)?;
+        Ok(self.getter(index))
+    }
+
     /// Gets a mutable accessor to the element at a given index, if it exists.
     ///
     /// Note: the accessor is protected by a [`StorageGuardMut`], which restricts
-    /// its lifetime to that of `&mut self`.
-    pub fn setter(&mut self, index: impl TryInto<usize>) -> Option<StorageGuardMut<S>> {
+    /// its lifetime to that of `&mut self`. This prevents holding two mutable accessors at once:
+    ///
+    /// ```compile_fail,E0499
+    /// use stylus_sdk::storage::{StorageVec, StorageType, StorageU256};
+    /// use stylus_sdk::alloy_primitives::U256;
+    ///
+    /// let mut vec: StorageVec<StorageU256> = unsafe { StorageVec::new(U256::ZERO, 0) };
+    /// let mut first = vec.setter(0_usize).unwrap();
+    /// let second = vec.setter(0_usize).unwrap(); // `vec` is already mutably borrowed
+    /// first.set(U256::from(1));
+    /// ```
+    pub fn setter(&mut self, index: impl TryInto<usize>) -> Option<StorageGuardMut<'_, S>> {
         let store = unsafe { self.accessor(index)? };
         Some(StorageGuardMut::new(store))
     }
@@ -114,9 +211,32 @@ impl<S: StorageType> StorageVec<S> {
         Some(store.load_mut())
     }
 
+    /// Returns an iterator over accessors to each element, reading the length once.
+    pub fn iter(&self) -> StorageVecIter<'_, S> {
+        StorageVecIter {
+            vec: self,
+            front: 0,
+            back: self.len(),
+        }
+    }
+
+    /// Returns an iterator over mutable accessors to each element, reading the length once.
+    pub fn iter_mut(&mut self) -> StorageVecIterMut<'_, S> {
+        let back = self.len();
+        StorageVecIterMut {
+            vec: self,
+            front: 0,
+            back,
+        }
+    }
+
     /// Like [`std::vec::Vec::push`][vec_push], but returns a mutable accessor to the new slot.
     /// This enables pushing elements without constructing them first.
     ///
+    /// This is the canonical way to append composite elements, such as nested vectors, maps,
+    /// and structs, which can't be constructed as plain values. For simple element types,
+    /// [`push`](Self::push) is built on top of this method.
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -133,9 +253,14 @@ impl<S: StorageType> StorageVec<S> {
     /// ```
     ///
     /// [vec_push]: https://doc.rust-lang.org/std/vec/struct.Vec.html#method.push
+    ///
+    /// # Panics
+    ///
+    /// If the vector's length would overflow.
     pub fn grow(&mut self) -> StorageGuardMut<S> {
         let index = self.len();
-        unsafe { self.set_len(index + 1) };
+        let len = index.checked_add(1).expect("StorageVec length overflow");
+        unsafe { self.set_len(len) };
 
         let (slot, offset) = self.index_slot(index);
         let store = unsafe { S::new(slot, offset) };
@@ -164,13 +289,37 @@ impl<S: StorageType> StorageVec<S> {
         }
     }
 
+    /// Removes all elements, zeroing every word they occupied so that no stale data remains.
+    /// Packed elements are cleared a whole word at a time.
+    ///
+    /// Each word costs an `SSTORE`, so this is linear in the number of words the elements span,
+    /// though zeroing words that held data earns a partial gas refund.
+    ///
+    /// Note: words belonging to the elements themselves, such as those of nested vectors,
+    /// aren't zeroed. To recursively erase those, see [`Erase`].
+    pub fn clear(&mut self) {
+        for i in 0..self.data_words() {
+            let slot = self.base().wrapping_add(U256::from(i));
+            unsafe { Storage::clear_word(slot) };
+        }
+        unsafe { self.set_len(0) };
+    }
+
     /// Determines the slot and offset for the element at an index.
+    ///
+    /// The offset from the base is computed in [`U256`], so scaling a large index by the element's
+    /// width can't overflow a [`usize`] on 32-bit targets like `wasm32`.
+    ///
+    /// As in Solidity, the slot is computed with wrapping arithmetic and can't panic. This relies
+    /// on keccak's collision resistance: since a vector spans far fewer than 2^256 slots past its
+    /// hashed base, its elements won't overlap those of any other hash-derived region.
     fn index_slot(&self, index: usize) -> (U256, u8) {
         let width = S::SLOT_BYTES;
         let words = S::REQUIRED_SLOTS.max(1);
         let density = self.density();
 
-        let slot = self.base() + U256::from(words * index / density);
+        let delta = U256::from(index) * U256::from(words) / U256::from(density);
+        let slot = self.base().wrapping_add(delta);
         let offset = 32 - (width * (1 + index % density)) as u8;
         (slot, offset)
     }
@@ -189,14 +338,30 @@ impl<S: StorageType> StorageVec<S> {
 
 impl<'a, S: SimpleStorageType<'a>> StorageVec<S> {
     /// Adds an element to the end of the vector.
+    /// To append elements that aren't plain values, see [`grow`](Self::grow).
     pub fn push(&mut self, value: S::Wraps<'a>) {
         let mut store = self.grow();
         store.set_by_wrapped(value);
     }
 
+    /// Adds an element to the end of the vector, returning an error rather than panicking
+    /// if the vector can't grow. This is useful for contracts that must revert cleanly.
+    pub fn try_push(&mut self, value: S::Wraps<'a>) -> Result<(), StorageError> {
+        let index = self.try_len()?;
+        match index.checked_add(1) {
+            Some(len) if len <= Self::MAX_LEN => unsafe { self.set_len(len) },
+            _ => return Err(StorageError::LengthOverflow),
+        }
+
+        let mut store = unsafe { self.accessor_unchecked(index) };
+        store.set_by_wrapped(value);
+        Ok(())
+    }
+
     /// Removes and returns the last element of the vector, if it exists.
     ///
-    /// Note: the underlying storage slot is erased when all elements in a word are freed.
+    /// Note: the vacated bytes are zeroed, so no junk data is left behind for later operations.
+    /// The underlying storage slot is erased when all elements in a word are freed.
     pub fn pop(&mut self) -> Option<S::Wraps<'a>> {
         let store = unsafe { self.shrink()?.into_raw() };
         let index = self.len();
@@ -209,9 +374,324 @@ impl<'a, S: SimpleStorageType<'a>> StorageVec<S> {
             for i in 0..words {
                 unsafe { Storage::clear_word(slot + U256::from(i)) };
             }
+        } else {
+            let (slot, offset) = self.index_slot(index);
+            let zeros = [0; 32];
+            let field = (offset.into(), &zeros[..S::SLOT_BYTES]);
+            unsafe { Storage::set_fields(slot, &[field]) };
+        }
+        Some(value)
+    }
+
+    /// Removes and returns the element at `index`, replacing it with the last element.
+    /// This doesn't preserve the order of the remaining elements, but costs a constant number
+    /// of writes. Returns `None` if `index` is out of bounds.
+    ///
+    /// Note: as with [`pop`](Self::pop), the vacated bytes at the end are zeroed.
+    pub fn swap_remove(&mut self, index: usize) -> Option<S::Wraps<'a>> {
+        if index >= self.len() {
+            return None;
+        }
+        let value = unsafe { self.accessor_unchecked(index) }.into();
+        let last = self.pop()?;
+        if index < self.len() {
+            let mut store = unsafe { self.accessor_unchecked(index) };
+            store.set_by_wrapped(last);
+        }
+        Some(value)
+    }
+
+    /// Removes and returns the element at `index`, shifting all elements after it down by one.
+    /// Unlike [`swap_remove`](Self::swap_remove), this preserves the order of the remaining
+    /// elements, at the cost of rewriting each of them. Returns `None` if `index` is out of bounds.
+    ///
+    /// Note: as with [`pop`](Self::pop), the vacated bytes at the end are zeroed.
+    pub fn remove(&mut self, index: usize) -> Option<S::Wraps<'a>> {
+        let len = self.len();
+        if index >= len {
+            return None;
         }
+        let value = unsafe { self.accessor_unchecked(index) }.into();
+
+        // shift forward so that each element is read before its slot is overwritten
+        for i in index + 1..len {
+            let next = unsafe { self.accessor_unchecked(i) }.into();
+            let mut store = unsafe { self.accessor_unchecked(i - 1) };
+            store.set_by_wrapped(next);
+        }
+        self.pop();
         Some(value)
     }
+
+    /// Gets the value at the given index, or the default value if the index is out of range.
+    /// This is useful for sparse access patterns in which a missing element means "not set".
+    pub fn get_value_or_default(&self, index: impl TryInto<usize>) -> S::Wraps<'a>
+    where
+        S::Wraps<'a>: Default,
+    {
+        let store = unsafe { self.accessor(index) };
+        store.map(Into::into).unwrap_or_default()
+    }
+
+    /// Gets the values in the half-open range `start..end`, for paginating through the vector.
+    ///
+    /// Returns an empty vector if `start > end` or if `end` exceeds the vector's length.
+    pub fn get_range(&self, start: usize, end: usize) -> Vec<S::Wraps<'a>> {
+        if start > end || end > self.len() {
+            return Vec::new();
+        }
+        (start..end)
+            .map(|i| unsafe { self.accessor_unchecked(i) }.into())
+            .collect()
+    }
+
+    /// Removes all elements from the vector, returning their values.
+    ///
+    /// Unlike [`truncate`](Self::truncate), the underlying storage is zeroed
+    /// so that no stale data remains after the handoff.
+    pub fn take(&mut self) -> Vec<S::Wraps<'a>> {
+        let len = self.len();
+        let values = (0..len)
+            .map(|i| unsafe { self.accessor_unchecked(i) }.into())
+            .collect();
+        self.clear();
+        values
+    }
+
+    /// Appends copies of another vector's values, leaving `other` unchanged.
+    ///
+    /// `other` is read in full before any element is written, and the length is updated once.
+    ///
+    /// # Panics
+    ///
+    /// If the vector's length would overflow.
+    pub fn extend_from(&mut self, other: &Self) {
+        let values = other.get_range(0, other.len());
+        let start = self.len();
+        let len = start.checked_add(values.len());
+        unsafe { self.set_len(len.expect("StorageVec length overflow")) };
+
+        for (i, value) in values.into_iter().enumerate() {
+            let mut store = unsafe { self.accessor_unchecked(start + i) };
+            store.set_by_wrapped(value);
+        }
+    }
+
+    /// Copies the vector's length and elements to a vector rooted at `dest_slot`, returning
+    /// an accessor to the copy. This is useful for checkpointing a vector before modifying it.
+    ///
+    /// Note: as with [`truncate`](Self::truncate), any elements the destination held beyond
+    /// the copied length aren't erased.
+    ///
+    /// # Safety
+    ///
+    /// Aliases storage if any other accessor refers to the vector at `dest_slot`.
+    pub unsafe fn clone_to(&self, dest_slot: U256) -> Self {
+        let mut dest = Self::new(dest_slot, 0);
+        dest.truncate(0);
+        dest.extend_from(self);
+        dest
+    }
+
+    /// Reads the values into two vectors, the first holding those before `mid` and the second
+    /// those from `mid` onward. Unlike [`Vec::split_off`], storage is left unchanged.
+    ///
+    /// If `mid` exceeds the vector's length, the second vector is empty.
+    pub fn split_at_values(&self, mid: usize) -> (Vec<S::Wraps<'a>>, Vec<S::Wraps<'a>>) {
+        let len = self.len();
+        let mid = mid.min(len);
+        (self.get_range(0, mid), self.get_range(mid, len))
+    }
+
+    /// Reads the values of several vectors, concatenated in order into a single [`Vec`].
+    pub fn concat(vecs: &[&Self]) -> Vec<S::Wraps<'a>> {
+        let mut values = Vec::with_capacity(vecs.iter().map(|vec| vec.len()).sum());
+        for vec in vecs {
+            values.extend(vec.get_range(0, vec.len()));
+        }
+        values
+    }
+
+    /// Reads the values in chunks of `size`, starting from the end of the vector, mirroring
+    /// [`slice::rchunks`]. Each chunk keeps its values in order, and the chunk nearest the
+    /// front may be shorter than `size`. This is useful for paginating newest-first.
+    ///
+    /// # Panics
+    ///
+    /// If `size` is zero.
+    pub fn rchunks(&self, size: usize) -> impl Iterator<Item = Vec<S::Wraps<'a>>> + '_ {
+        assert!(size != 0, "chunk size must be non-zero");
+        let len = self.len();
+        let chunks = len / size + usize::from(len % size != 0);
+        (0..chunks).map(move |chunk| {
+            let end = len - chunk * size;
+            let start = end.saturating_sub(size);
+            (start..end)
+                .map(|i| unsafe { self.accessor_unchecked(i) }.into())
+                .collect()
+        })
+    }
+
+    /// Reads every value, applying `f` to each, and collects the results into a [`Vec`].
+    pub fn map_to_vec<T>(&self, f: impl FnMut(S::Wraps<'a>) -> T) -> Vec<T> {
+        (0..self.len())
+            .map(|i| unsafe { self.accessor_unchecked(i) }.into())
+            .map(f)
+            .collect()
+    }
+
+    /// Searches for a value from the end, returning the index of the last one satisfying `f`.
+    /// This is useful for finding the most recent matching entry in an append-only log.
+    pub fn rposition(&self, mut f: impl FnMut(&S::Wraps<'a>) -> bool) -> Option<usize> {
+        (0..self.len()).rev().find(|&i| {
+            let value = unsafe { self.accessor_unchecked(i) }.into();
+            f(&value)
+        })
+    }
+
+    /// Iterates over the values that aren't the default, along with their indices.
+    /// This is useful for skipping the holes left by tombstone-style deletion,
+    /// in which elements are zeroed rather than shifted.
+    pub fn iter_nonzero(&self) -> impl Iterator<Item = (usize, S::Wraps<'a>)> + '_
+    where
+        S::Wraps<'a>: Default + PartialEq,
+    {
+        (0..self.len())
+            .map(|i| (i, unsafe { self.accessor_unchecked(i) }.into()))
+            .filter(|(_, value)| *value != S::Wraps::default())
+    }
+
+    /// Counts the values satisfying `f`, reading each element once.
+    pub fn count(&self, mut f: impl FnMut(&S::Wraps<'a>) -> bool) -> usize {
+        (0..self.len())
+            .filter(|&i| {
+                let value = unsafe { self.accessor_unchecked(i) }.into();
+                f(&value)
+            })
+            .count()
+    }
+
+    /// Returns the index of the first value for which `pred` is false, assuming the vector is
+    /// partitioned so that every value satisfying `pred` comes first, mirroring
+    /// [`slice::partition_point`]. This is useful for finding insertion points in sorted data.
+    ///
+    /// Binary search reads only a logarithmic number of elements. If the vector isn't
+    /// partitioned, the returned index is unspecified.
+    pub fn partition_point(&self, mut pred: impl FnMut(&S::Wraps<'a>) -> bool) -> usize {
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let value = unsafe { self.accessor_unchecked(mid) }.into();
+            match pred(&value) {
+                true => low = mid + 1,
+                false => high = mid,
+            }
+        }
+        low
+    }
+
+    /// Totals the values in a [`U256`], reading each element once. Widening the accumulator
+    /// means many large values can be summed without overflow.
+    ///
+    /// Returns `None` if any value is negative or the total doesn't fit in a [`U256`].
+    pub fn sum(&self) -> Option<U256>
+    where
+        U256: UintTryFrom<S::Wraps<'a>>,
+    {
+        (0..self.len()).try_fold(U256::ZERO, |total, i| {
+            let value: S::Wraps<'a> = unsafe { self.accessor_unchecked(i) }.into();
+            total.checked_add(U256::uint_try_from(value).ok()?)
+        })
+    }
+
+    /// Returns the smallest value, or `None` if the vector is empty.
+    pub fn min(&self) -> Option<S::Wraps<'a>>
+    where
+        S::Wraps<'a>: Ord,
+    {
+        (0..self.len())
+            .map(|i| unsafe { self.accessor_unchecked(i) }.into())
+            .min()
+    }
+
+    /// Returns the largest value, or `None` if the vector is empty.
+    pub fn max(&self) -> Option<S::Wraps<'a>>
+    where
+        S::Wraps<'a>: Ord,
+    {
+        (0..self.len())
+            .map(|i| unsafe { self.accessor_unchecked(i) }.into())
+            .max()
+    }
+
+    /// Returns `true` if both vectors have the same length and equal values at every index.
+    /// Elements are compared in lockstep, stopping at the first mismatch.
+    pub fn eq_contents(&self, other: &Self) -> bool
+    where
+        S::Wraps<'a>: PartialEq,
+    {
+        let len = self.len();
+        if len != other.len() {
+            return false;
+        }
+        (0..len).all(|i| {
+            let value: S::Wraps<'a> = unsafe { self.accessor_unchecked(i) }.into();
+            value == unsafe { other.accessor_unchecked(i) }.into()
+        })
+    }
+
+    /// Exchanges the element at `index` with the element at `other_index` in another vector.
+    ///
+    /// # Panics
+    ///
+    /// If either index is out of bounds.
+    pub fn swap_with(&mut self, index: usize, other: &mut Self, other_index: usize) {
+        assert!(index < self.len(), "StorageVec index out of bounds");
+        assert!(other_index < other.len(), "StorageVec index out of bounds");
+
+        // intentionally alias so that we can write back after load
+        let mut store = unsafe { self.accessor_unchecked(index) };
+        let mut other_store = unsafe { other.accessor_unchecked(other_index) };
+        let value = unsafe { self.accessor_unchecked(index) }.into();
+        let other_value = unsafe { other.accessor_unchecked(other_index) }.into();
+        store.set_by_wrapped(other_value);
+        other_store.set_by_wrapped(value);
+    }
+
+    /// Applies `f` to every element in place, passing each element's index.
+    ///
+    /// Each value is read, handed to `f` for mutation, and written back, in a single pass
+    /// that reads the length only once.
+    pub fn for_each_mut(&mut self, mut f: impl FnMut(usize, &mut S::Wraps<'a>)) {
+        for i in 0..self.len() {
+            // intentionally alias so that we can write back after load
+            let mut store = unsafe { self.accessor_unchecked(i) };
+            let mut value = unsafe { self.accessor_unchecked(i) }.into();
+            f(i, &mut value);
+            store.set_by_wrapped(value);
+        }
+    }
+}
+
+impl<const B: usize, const L: usize> StorageVec<StorageUint<B, L>> {
+    /// Iterates over the values, loading each backing word once and decoding every element
+    /// packed within it before moving on. For dense element types like [`StorageU8`], this
+    /// avoids reading the same word once per element, as [`get`](Self::get) would.
+    ///
+    /// Note: the length is read when the iterator is created.
+    ///
+    /// [`StorageU8`]: super::StorageU8
+    pub fn iter_packed(&self) -> impl Iterator<Item = Uint<B, L>> + '_ {
+        let mut word = B256::ZERO;
+        (0..self.len()).map(move |i| {
+            let (slot, offset) = self.index_slot(i);
+            if i % self.density() == 0 {
+                word = Storage::get_word(slot);
+            }
+            let offset = offset as usize;
+            Uint::from_be_slice(&word[offset..offset + B / 8])
+        })
+    }
 }
 
 impl<S: Erase> StorageVec<S> {
@@ -238,6 +718,85 @@ impl<S: Erase> Erase for StorageVec<S> {
     }
 }
 
+/// Iterator over accessors to the elements of a [`StorageVec`], created by
+/// [`StorageVec::iter`].
+pub struct StorageVecIter<'a, S: StorageType> {
+    vec: &'a StorageVec<S>,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, S: StorageType> Iterator for StorageVecIter<'a, S> {
+    type Item = StorageGuard<'a, S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        let store = unsafe { self.vec.accessor_unchecked(self.front) };
+        self.front += 1;
+        Some(StorageGuard::new(store))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<'a, S: StorageType> DoubleEndedIterator for StorageVecIter<'a, S> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        let store = unsafe { self.vec.accessor_unchecked(self.back) };
+        Some(StorageGuard::new(store))
+    }
+}
+
+impl<'a, S: StorageType> ExactSizeIterator for StorageVecIter<'a, S> {}
+
+/// Iterator over mutable accessors to the elements of a [`StorageVec`], created by
+/// [`StorageVec::iter_mut`].
+pub struct StorageVecIterMut<'a, S: StorageType> {
+    vec: &'a mut StorageVec<S>,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, S: StorageType> Iterator for StorageVecIterMut<'a, S> {
+    type Item = StorageGuardMut<'a, S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        // each element is yielded once, so the accessors never alias
+        let store = unsafe { self.vec.accessor_unchecked(self.front) };
+        self.front += 1;
+        Some(StorageGuardMut::new(store))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<'a, S: StorageType> DoubleEndedIterator for StorageVecIterMut<'a, S> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        let store = unsafe { self.vec.accessor_unchecked(self.back) };
+        Some(StorageGuardMut::new(store))
+    }
+}
+
+impl<'a, S: StorageType> ExactSizeIterator for StorageVecIterMut<'a, S> {}
+
 impl<'a, S: SimpleStorageType<'a>> Extend<S::Wraps<'a>> for StorageVec<S> {
     fn extend<T: IntoIterator<Item = S::Wraps<'a>>>(&mut self, iter: T) {
         for elem in iter {
@@ -245,3 +804,466 @@ impl<'a, S: SimpleStorageType<'a>> Extend<S::Wraps<'a>> for StorageVec<S> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{
+        testing, StorageArray, StorageCache, StorageMap, StorageU256, StorageU64, StorageU8,
+    };
+    use alloy_primitives::{B256, U64, U8};
+
+    fn vec_of(values: &[u64]) -> StorageVec<StorageU64> {
+        let mut vec: StorageVec<StorageU64> = unsafe { StorageVec::new(U256::ZERO, 0) };
+        vec.extend(values.iter().map(|x| U64::from(*x)));
+        vec
+    }
+
+    fn u64s(values: Vec<U64>) -> Vec<u64> {
+        values.into_iter().map(|x| x.to()).collect()
+    }
+
+    /// A struct holding a vector of maps, laid out as the `storage` macro would lay it out.
+    struct Registry {
+        version: StorageU64,
+        books: StorageVec<StorageMap<u8, StorageU64>>,
+    }
+
+    impl Registry {
+        unsafe fn new(slot: U256) -> Self {
+            Self {
+                version: StorageU64::new(slot, 24),
+                books: StorageVec::new(slot + U256::from(1), 0),
+            }
+        }
+    }
+
+    #[test]
+    fn test_slot_count() {
+        let mut vec = vec_of(&[]);
+        assert_eq!(vec.slot_count(), 1);
+        vec.extend([1, 2, 3, 4, 5].map(U64::from));
+        assert_eq!(vec.slot_count(), 3);
+
+        let mut nested: StorageVec<StorageVec<StorageU64>> =
+            unsafe { StorageVec::new(U256::from(1), 0) };
+        nested.grow().push(U64::from(1));
+        assert_eq!(nested.slot_count(), 2);
+    }
+
+    #[test]
+    fn test_len_invalidated() {
+        let mut vec = vec_of(&[1, 2]);
+        assert!(!vec.is_empty());
+        unsafe { Storage::set_word(U256::ZERO, U256::from(5).into()) };
+        assert_eq!(vec.len(), 5);
+        vec.push(U64::from(3));
+        assert_eq!(vec.len(), 6);
+
+        let fresh: StorageVec<StorageU64> = unsafe { StorageVec::new(U256::ZERO, 0) };
+        assert_eq!(fresh.len(), 6);
+    }
+
+    #[test]
+    fn test_index_slot_wraps() {
+        let vec = vec_of(&[]);
+        let (slot, offset) = vec.index_slot(usize::MAX);
+        let delta = U256::from(usize::MAX / 4);
+        assert_eq!((slot, offset), (vec.data_base().wrapping_add(delta), 0));
+
+        let wide: StorageVec<StorageVec<StorageU64>> = unsafe { StorageVec::new(U256::ZERO, 0) };
+        let slot = wide.data_base().wrapping_add(U256::from(usize::MAX));
+        assert_eq!(wide.index_slot(usize::MAX).0, slot);
+    }
+
+    #[test]
+    fn test_index_slot_multi_word() {
+        let pairs: StorageVec<StorageArray<StorageU256, 2>> =
+            unsafe { StorageVec::new(U256::ZERO, 0) };
+        let index = usize::MAX / 2 + 1;
+        let delta = U256::from(index) * U256::from(2);
+        assert!(index.checked_mul(2).is_none());
+        assert_eq!(pairs.index_slot(index), (pairs.data_base() + delta, 0));
+    }
+
+    #[test]
+    fn test_raw_words() {
+        let vec = vec_of(&[1, 2, 3, 4, 5]);
+        let words = vec.raw_words();
+        assert_eq!(words.len(), 2);
+        assert_eq!((words[0][31], words[0][7], words[1][31]), (1, 4, 5));
+        let empty: StorageVec<StorageU64> = unsafe { StorageVec::new(U256::from(1), 0) };
+        assert!(empty.raw_words().is_empty());
+    }
+
+    #[test]
+    fn test_get_past_end() {
+        let mut vec = vec_of(&[1, 2, 3]);
+        assert_eq!(vec.get(2_usize), Some(U64::from(3)));
+        assert_eq!(vec.get(3_usize), None);
+        assert!(vec.getter(3_usize).is_none() && vec.setter(3_usize).is_none());
+
+        assert_eq!(vec.pop(), Some(U64::from(3)));
+        assert_eq!(vec.get(2_usize), None);
+    }
+
+    #[test]
+    fn test_get_checked() {
+        let vec = vec_of(&[1]);
+        let value = vec.get_checked(U256::ZERO).unwrap().unwrap().get();
+        assert_eq!(value, U64::from(1));
+        assert!(vec.get_checked(U256::from(1)).unwrap().is_none());
+        assert_eq!(
+            vec.get_checked(U256::MAX).err(),
+            Some(StorageError::IndexOverflow)
+        );
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut bytes: StorageVec<StorageU8> = unsafe { StorageVec::new(U256::ZERO, 0) };
+        bytes.extend((1..=40).map(U8::from));
+        let base = bytes.data_base();
+        bytes.clear();
+        assert!(bytes.is_empty());
+        for i in 0..2 {
+            assert_eq!(Storage::get_word(base + U256::from(i)), B256::ZERO);
+        }
+
+        let mut pairs: StorageVec<StorageArray<StorageU256, 2>> =
+            unsafe { StorageVec::new(U256::from(1), 0) };
+        pairs.grow().setter(1_usize).unwrap().set(U256::from(7));
+        pairs.clear();
+        let second = pairs.data_base() + U256::from(1);
+        assert_eq!(Storage::get_word(second), B256::ZERO);
+    }
+
+    #[test]
+    fn test_swap_remove() {
+        let mut vec = vec_of(&[1, 2, 3, 4, 5]);
+        assert_eq!(vec.swap_remove(5), None);
+        assert_eq!(vec.swap_remove(1), Some(U64::from(2)));
+        assert_eq!(u64s(vec.get_range(0, vec.len())), [1, 5, 3, 4]);
+
+        assert_eq!(vec.swap_remove(3), Some(U64::from(4)));
+        assert_eq!(vec.swap_remove(0), Some(U64::from(1)));
+        assert_eq!(u64s(vec.get_range(0, vec.len())), [3, 5]);
+        assert_eq!(vec.raw_words()[0][15], 0);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut vec = vec_of(&[1, 2, 3, 4, 5, 6]);
+        assert_eq!(vec.remove(6), None);
+        assert_eq!(vec.remove(1), Some(U64::from(2)));
+        assert_eq!(u64s(vec.get_range(0, vec.len())), [1, 3, 4, 5, 6]);
+
+        assert_eq!(vec.remove(4), Some(U64::from(6)));
+        assert_eq!(vec.remove(0), Some(U64::from(1)));
+        assert_eq!(u64s(vec.get_range(0, vec.len())), [3, 4, 5]);
+        let words = vec.raw_words();
+        assert_eq!((words.len(), words[0][7]), (1, 0));
+        let next = vec.data_base() + U256::from(1);
+        assert_eq!(Storage::get_word(next), B256::ZERO);
+    }
+
+    #[test]
+    fn test_get_value_or_default() {
+        let vec = vec_of(&[4, 5]);
+        assert_eq!(vec.get_value_or_default(1), U64::from(5));
+        assert_eq!(vec.get_value_or_default(2), U64::ZERO);
+        assert_eq!(vec.get_value_or_default(U256::MAX), U64::ZERO);
+    }
+
+    #[test]
+    fn test_get_range() {
+        let vec = vec_of(&[1, 2, 3, 4, 5]);
+        assert_eq!(u64s(vec.get_range(1, 3)), [2, 3]);
+        assert_eq!(u64s(vec.get_range(0, 5)), [1, 2, 3, 4, 5]);
+        assert!(vec.get_range(2, 2).is_empty());
+        assert!(vec.get_range(3, 6).is_empty());
+        assert!(vec.get_range(3, 2).is_empty());
+    }
+
+    #[test]
+    fn test_layout_anchors() {
+        let vec = vec_of(&[]);
+        assert_eq!(vec.header_slot(), U256::ZERO);
+        assert_eq!(vec.data_base(), crypto::keccak(B256::ZERO).into());
+    }
+
+    #[test]
+    fn test_split_at_values() {
+        let vec = vec_of(&[1, 2, 3]);
+        let (settled, pending) = vec.split_at_values(1);
+        assert_eq!((u64s(settled), u64s(pending)), (vec![1], vec![2, 3]));
+
+        let (settled, pending) = vec.split_at_values(5);
+        assert_eq!((u64s(settled), u64s(pending)), (vec![1, 2, 3], vec![]));
+    }
+
+    #[test]
+    fn test_concat() {
+        let pending = vec_of(&[1, 2]);
+        let mut active: StorageVec<StorageU64> = unsafe { StorageVec::new(U256::from(1), 0) };
+        active.push(U64::from(3));
+        assert_eq!(u64s(StorageVec::concat(&[&pending, &active])), [1, 2, 3]);
+        assert!(StorageVec::<StorageU64>::concat(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_extend_from() {
+        let snapshot = vec_of(&[1, 2, 3]);
+        let mut working: StorageVec<StorageU64> = unsafe { StorageVec::new(U256::from(1), 0) };
+        working.push(U64::from(9));
+        working.extend_from(&snapshot);
+        assert_eq!(u64s(working.get_range(0, working.len())), [9, 1, 2, 3]);
+        assert_eq!(u64s(snapshot.get_range(0, snapshot.len())), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_clone_to() {
+        let mut vec = vec_of(&[1, 2, 3]);
+        let copy = unsafe { vec.clone_to(U256::from(1)) };
+        vec.push(U64::from(4));
+        assert_eq!(u64s(copy.get_range(0, copy.len())), [1, 2, 3]);
+
+        let copy = unsafe { vec.clone_to(U256::from(1)) };
+        assert_eq!(u64s(copy.get_range(0, copy.len())), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_rchunks() {
+        let vec = vec_of(&[1, 2, 3, 4, 5]);
+        let chunks: Vec<_> = vec.rchunks(2).map(u64s).collect();
+        assert_eq!(chunks, [vec![4, 5], vec![2, 3], vec![1]]);
+        assert_eq!(vec.rchunks(8).count(), 1);
+    }
+
+    #[test]
+    fn test_map_to_vec() {
+        let vec = vec_of(&[1, 2, 3]);
+        assert_eq!(vec.map_to_vec(|x| x.to::<u64>() * 2), [2, 4, 6]);
+    }
+
+    #[test]
+    fn test_rposition() {
+        let vec = vec_of(&[5, 1, 5, 2]);
+        assert_eq!(vec.rposition(|x| *x == U64::from(5)), Some(2));
+        assert_eq!(vec.rposition(|x| *x > U64::from(9)), None);
+    }
+
+    #[test]
+    fn test_iter_nonzero() {
+        let vec = vec_of(&[0, 5, 0, 7]);
+        let live: Vec<_> = vec.iter_nonzero().collect();
+        assert_eq!(live, [(1, U64::from(5)), (3, U64::from(7))]);
+    }
+
+    #[test]
+    fn test_push_packed_flush() {
+        let mut bytes: StorageVec<StorageU8> = unsafe { StorageVec::new(U256::ZERO, 0) };
+        for i in 0..40 {
+            bytes.push(U8::from(i));
+        }
+        StorageCache::flush();
+
+        let base = bytes.data_base();
+        let first = testing::persisted(base);
+        let second = testing::persisted(base + U256::from(1));
+        assert_eq!((first[31], first[0]), (0, 31));
+        assert_eq!((second[31], second[24]), (32, 39));
+        assert_eq!(testing::persisted(U256::ZERO)[31], 40);
+
+        let fresh: StorageVec<StorageU8> = unsafe { StorageVec::new(U256::ZERO, 0) };
+        let values: Vec<u8> = fresh.get_range(0, 40).iter().map(|x| x.to()).collect();
+        assert_eq!(values, (0..40).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut vec: StorageVec<StorageU256> = unsafe { StorageVec::new(U256::ZERO, 0) };
+        vec.extend([1, 2, 3].map(U256::from));
+        let sum: U256 = vec.iter().map(|g| g.get()).sum();
+        assert_eq!(sum, U256::from(6));
+        assert_eq!(vec.iter().len(), 3);
+        assert_eq!(vec.iter().next_back().unwrap().get(), U256::from(3));
+
+        for mut store in vec.iter_mut().rev().skip(1) {
+            let value = store.get();
+            store.set(value * U256::from(10));
+        }
+        let values: Vec<U256> = vec.iter().map(|g| g.get()).collect();
+        assert_eq!(values, [10, 20, 3].map(U256::from));
+    }
+
+    #[test]
+    fn test_iter_packed() {
+        let mut bytes: StorageVec<StorageU8> = unsafe { StorageVec::new(U256::from(1), 0) };
+        bytes.extend((0..40).map(U8::from));
+        let values: Vec<u8> = bytes.iter_packed().map(|x| x.to()).collect();
+        assert_eq!(values, (0..40).collect::<Vec<u8>>());
+
+        let vec = vec_of(&[1, 2, 3, 4, 5]);
+        assert_eq!(u64s(vec.iter_packed().collect()), [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_count() {
+        let vec = vec_of(&[0, 5, 0, 7]);
+        assert_eq!(vec.count(|value| !value.is_zero()), 2);
+        assert_eq!(vec.count(|_| false), 0);
+    }
+
+    #[test]
+    fn test_partition_point() {
+        let vec = vec_of(&[10, 20, 20, 30]);
+        assert_eq!(vec.partition_point(|x| *x < U64::from(20)), 1);
+        assert_eq!(vec.partition_point(|x| *x <= U64::from(20)), 3);
+        assert_eq!(vec.partition_point(|_| true), 4);
+        assert_eq!(vec.partition_point(|_| false), 0);
+    }
+
+    #[test]
+    fn test_sum() {
+        let vec = vec_of(&[u64::MAX, u64::MAX, 2]);
+        let expected = U256::from(u64::MAX) * U256::from(2) + U256::from(2);
+        assert_eq!(vec.sum(), Some(expected));
+
+        let mut wide: StorageVec<StorageU256> = unsafe { StorageVec::new(U256::from(1), 0) };
+        assert_eq!(wide.sum(), Some(U256::ZERO));
+        wide.extend([U256::MAX, U256::from(1)]);
+        assert_eq!(wide.sum(), None);
+    }
+
+    #[test]
+    fn test_min_max() {
+        let mut vec = vec_of(&[4, 1, 9, 3]);
+        assert_eq!(vec.min(), Some(U64::from(1)));
+        assert_eq!(vec.max(), Some(U64::from(9)));
+        vec.take();
+        assert_eq!(vec.min(), None);
+    }
+
+    #[test]
+    fn test_eq_contents() {
+        let vec = vec_of(&[1, 2, 3]);
+        let mut other: StorageVec<StorageU64> = unsafe { StorageVec::new(U256::from(1), 0) };
+        other.extend([U64::from(1), U64::from(2)]);
+        assert!(!vec.eq_contents(&other));
+
+        other.push(U64::from(3));
+        assert!(vec.eq_contents(&other));
+
+        other.setter(1).unwrap().set(U64::from(5));
+        assert!(!vec.eq_contents(&other));
+    }
+
+    #[test]
+    fn test_swap_with() {
+        let mut pool = vec_of(&[1, 2, 3]);
+        let mut other: StorageVec<StorageU64> = unsafe { StorageVec::new(U256::from(1), 0) };
+        other.extend([U64::from(7), U64::from(8)]);
+
+        pool.swap_with(2, &mut other, 0);
+        assert_eq!(u64s(pool.get_range(0, 3)), [1, 2, 7]);
+        assert_eq!(u64s(other.get_range(0, 2)), [3, 8]);
+    }
+
+    #[test]
+    #[should_panic(expected = "StorageVec index out of bounds")]
+    fn test_swap_with_bounds() {
+        let mut pool = vec_of(&[1]);
+        let mut other: StorageVec<StorageU64> = unsafe { StorageVec::new(U256::from(1), 0) };
+        pool.swap_with(0, &mut other, 0);
+    }
+
+    #[test]
+    fn test_for_each_mut() {
+        let mut vec = vec_of(&[1, 2, 3]);
+        vec.for_each_mut(|i, value| *value += U64::from(10 * i));
+        assert_eq!(u64s(vec.get_range(0, 3)), [1, 12, 23]);
+    }
+
+    #[test]
+    fn test_pop_zeroes() {
+        let mut vec = vec_of(&[1, 2, 3]);
+        assert_eq!(vec.pop(), Some(U64::from(3)));
+        assert_eq!(vec.pop(), Some(U64::from(2)));
+
+        let mut expected = B256::ZERO;
+        expected[31] = 1;
+        assert_eq!(Storage::get_word(vec.data_base()), expected);
+        unsafe { vec.set_len(3) };
+        assert_eq!(u64s(vec.get_range(0, 3)), [1, 0, 0]);
+    }
+
+    #[test]
+    fn test_try_push() {
+        let mut vec = vec_of(&[1]);
+        assert_eq!(vec.try_push(U64::from(2)), Ok(()));
+        assert_eq!(u64s(vec.get_range(0, 2)), [1, 2]);
+
+        let overflow = Err(StorageError::LengthOverflow);
+        unsafe { vec.set_len(usize::MAX) };
+        assert_eq!(vec.try_push(U64::from(3)), overflow);
+        unsafe { Storage::set_word(U256::ZERO, U256::MAX.into()) };
+        let mut vec: StorageVec<StorageU64> = unsafe { StorageVec::new(U256::ZERO, 0) };
+        assert_eq!(vec.try_push(U64::from(3)), overflow);
+    }
+
+    #[test]
+    fn test_aliased_push() {
+        let mut a = vec_of(&[]);
+        a.push(U64::from(1));
+        let mut b: StorageVec<StorageU64> = unsafe { StorageVec::new(U256::ZERO, 0) };
+        assert_eq!(b.len(), 1);
+        b.push(U64::from(2));
+        a.push(U64::from(3));
+        assert_eq!(a.try_push(U64::from(4)), Ok(()));
+
+        assert_eq!(a.len(), 4);
+        assert_eq!(b.len(), 4);
+        assert_eq!(u64s(b.get_range(0, 4)), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "StorageVec length overflow")]
+    fn test_push_overflow() {
+        let mut vec = vec_of(&[]);
+        unsafe { vec.set_len(usize::MAX) };
+        vec.push(U64::from(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "StorageVec length exceeds usize")]
+    fn test_corrupt_len() {
+        let vec = vec_of(&[]);
+        unsafe { Storage::set_word(U256::ZERO, U256::MAX.into()) };
+        vec.len();
+    }
+
+    #[test]
+    fn test_nested_composites() {
+        let root = U256::from(3);
+        let mut registry = unsafe { Registry::new(root) };
+        registry.version.set(U64::from(1));
+        registry.books.grow().insert(7, U64::from(70));
+        registry.books.grow().insert(7, U64::from(71));
+
+        let base = crypto::keccak((root + U256::from(1)).to_be_bytes::<32>());
+        for (i, value) in [(0, 70), (1, 71)] {
+            let book = U256::from_be_bytes(base.0) + U256::from(i);
+            let mut preimage = [0; 64];
+            preimage[31] = 7;
+            preimage[32..].copy_from_slice(&book.to_be_bytes::<32>());
+            let slot = crypto::keccak(preimage).into();
+            assert_eq!(Storage::get_word(slot)[31], value);
+        }
+
+        let registry = unsafe { Registry::new(root) };
+        assert_eq!(registry.version.get(), U64::from(1));
+        assert_eq!(registry.books.len(), 2);
+        assert_eq!(registry.books.getter(1).unwrap().get(7), U64::from(71));
+    }
+}
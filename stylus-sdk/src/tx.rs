@@ -39,8 +39,20 @@ pub fn ink_to_gas(ink: u64) -> u64 {
     ink / ink_price() as u64
 }
 
+/// Gets the amount of gas remaining. This is an alias for [`evm::gas_left`], provided alongside
+/// [`ink_to_gas`] for contracts that reason about their budget in this module's terms.
+/// This is useful for bailing out of expensive loops before running out of gas.
+///
+/// [`evm::gas_left`]: crate::evm::gas_left
+pub fn gas_left() -> u64 {
+    crate::evm::gas_left()
+}
+
 wrap_hostio!(
     /// Gets the gas price in wei per gas, which on Arbitrum chains equals the basefee.
+    ///
+    /// Since Arbitrum chains don't pay priority fees, this is also the effective gas price,
+    /// and can be used directly in fee-refund arithmetic.
     gas_price GAS_PRICE tx_gas_price U256
 );
 